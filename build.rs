@@ -0,0 +1,47 @@
+//! Generates the module list of the `advent` binary from the scaffolded days in `src/bin`.
+use std::{env, fs, path::Path};
+
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let bin_dir = Path::new(&manifest_dir).join("src").join("bin");
+
+    println!("cargo::rerun-if-changed=src/bin");
+
+    let mut days: Vec<String> = fs::read_dir(&bin_dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .filter_map(|name| {
+                    let stem = name.strip_suffix(".rs")?;
+                    (stem.len() == 2 && stem.chars().all(|c| c.is_ascii_digit()))
+                        .then(|| stem.to_string())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    days.sort_unstable();
+
+    // days are compiled as modules of the `advent` binary. their tests already run as part of
+    // their own bins, and their `dhat` allocators would clash with each other.
+    let cfg = r#"#[cfg(not(any(test, feature = "dhat-heap")))]"#;
+
+    let mut out = String::new();
+    for day in &days {
+        let path = bin_dir.join(format!("{day}.rs"));
+        out.push_str(&format!(
+            "{cfg}\n#[allow(dead_code, unused_imports)]\n#[path = {:?}]\nmod day{day};\n\n",
+            path.display().to_string()
+        ));
+    }
+
+    out.push_str("/// Solutions of all scaffolded days.\n");
+    out.push_str("pub const SOLUTIONS: &[advent_of_code::template::registry::Entry] = &[\n");
+    for day in &days {
+        out.push_str(&format!("    {cfg}\n    day{day}::SOLUTION,\n"));
+    }
+    out.push_str("];\n");
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("registry.rs"), out).unwrap();
+}
@@ -1,15 +1,13 @@
 use std::collections::HashMap;
+use std::hash::Hash;
+
+use advent_of_code::parsing::columns;
+
 advent_of_code::solution!(1);
 
 pub fn part_one(input: &str) -> Option<u64> {
-    let mut left: Vec<u32> = Vec::new();
-    let mut right: Vec<u32> = Vec::new();
+    let [mut left, mut right] = columns::<u32, 2>(input).expect("unable to parse input file");
 
-    for line in input.lines() {
-        let mut items = line.split_whitespace();
-        left.push(items.next().unwrap().parse::<u32>().unwrap());
-        right.push(items.next().unwrap().parse::<u32>().unwrap());
-    }
     // sort the 2 lists
     left.sort();
     right.sort();
@@ -22,14 +20,8 @@ pub fn part_one(input: &str) -> Option<u64> {
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    let mut left: Vec<&str> = Vec::new();
-    let mut right: Vec<&str> = Vec::new();
+    let [mut left, mut right] = columns::<u32, 2>(input).expect("unable to parse input file");
 
-    for line in input.lines() {
-        let mut items = line.split_whitespace();
-        left.push(items.next().unwrap());
-        right.push(items.next().unwrap());
-    }
     // sort the 2 lists
     left.sort();
     right.sort();
@@ -39,22 +31,22 @@ pub fn part_two(input: &str) -> Option<u64> {
     let total_nonzero: usize = left_in_right
         .into_iter()
         .filter(|&v| v.1 > 0)
-        .map(|v| v.0.parse::<usize>().unwrap() * v.1)
+        .map(|v| v.0 as usize * v.1)
         .sum();
 
     Some(total_nonzero.try_into().unwrap())
 }
 
-fn counts_for_a_in_b<'a>(a: &'a [&'a str], b: &'a [&'a str]) -> HashMap<&'a str, usize> {
-    let mut b_counts: HashMap<&'a str, usize> = HashMap::new();
+fn counts_for_a_in_b<T: Copy + Eq + Hash>(a: &[T], b: &[T]) -> HashMap<T, usize> {
+    let mut b_counts: HashMap<T, usize> = HashMap::new();
     for &s in b {
         *b_counts.entry(s).or_insert(0) += 1;
     }
 
-    let mut out: HashMap<&'a str, usize> = HashMap::with_capacity(a.len());
+    let mut out: HashMap<T, usize> = HashMap::with_capacity(a.len());
     for &s in a {
         // entry() ensures duplicates in `a` don’t redo work
-        out.entry(s).or_insert(*b_counts.get(s).unwrap_or(&0));
+        out.entry(s).or_insert(*b_counts.get(&s).unwrap_or(&0));
     }
     out
 }
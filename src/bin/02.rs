@@ -1,10 +1,13 @@
-use advent_of_code::{is_line_safe, is_safe_monotonic};
+use advent_of_code::report::Rules;
+use advent_of_code::template;
 
 advent_of_code::solution!(2);
 
-pub fn part_one(input: &str) -> Option<u64> {
+/// Number of reports that are safe under `rules`. With `--explain`, prints the verdict of every
+/// report, e.g. `1 3 2 4 5: unsafe: 3 -> 2 at index 1 is not increasing`.
+fn count_safe(input: &str, rules: &Rules) -> u64 {
+    let explain = template::explain_requested();
     let mut success_count = 0u64;
-    let mut _fail_count = 0u64;
 
     for line in input.lines().filter(|l| !l.trim().is_empty()) {
         // Parse the line into numbers (u32 → cast to i64 for signed diffs)
@@ -14,55 +17,129 @@ pub fn part_one(input: &str) -> Option<u64> {
             .collect::<Result<Vec<_>, _>>()
         {
             Ok(v) => v,
-            Err(_) => {
+            Err(e) => {
                 // Bad line → count as fail and skip
-                _fail_count += 1;
+                if explain {
+                    println!("{line}: invalid, {e}");
+                }
                 continue;
             }
         };
 
-        if is_safe_monotonic(&nums) {
+        let verdict = rules.check(&nums);
+        if explain {
+            println!("{line}: {verdict}");
+        }
+        if verdict.is_safe() {
             success_count += 1;
-        } else {
-            _fail_count += 1;
         }
     }
 
-    Some(success_count)
+    success_count
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
-    let mut success_count = 0u64;
-    let mut _fail_count = 0u64;
-
-    for line in input.lines().filter(|l| !l.trim().is_empty()) {
-        // Parse the line into numbers (u32 → cast to i64 for signed diffs)
-        let nums: Vec<i64> = match line
-            .split_whitespace()
-            .map(|tok| tok.parse::<u32>().map(|x| x as i64))
-            .collect::<Result<Vec<_>, _>>()
-        {
-            Ok(v) => v,
-            Err(_) => {
-                // Bad line → count as fail and skip
-                _fail_count += 1;
-                continue;
-            }
-        };
-
-        if is_line_safe(&nums) {
-            success_count += 1;
-        } else {
-            _fail_count += 1;
-        }
-    }
+pub fn part_one(input: &str) -> Option<u64> {
+    Some(count_safe(input, &Rules::default()))
+}
 
-    Some(success_count)
+pub fn part_two(input: &str) -> Option<u64> {
+    let rules = Rules {
+        max_removals: 1,
+        ..Rules::default()
+    };
+    Some(count_safe(input, &rules))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::property::{self, Rng};
+    use advent_of_code::report::{Direction, Verdict, Violation};
+    use advent_of_code::{is_line_safe, is_safe_monotonic};
+
+    /// With at least 3 levels, removing one still leaves a pair of levels to check. Shorter
+    /// reports would hit the convention that a single level is not a safe report, where the
+    /// brute force and the solutions need not agree.
+    const MIN_LEVELS: usize = 3;
+
+    /// Random report: mostly valid steps in one direction, with the occasional bad level.
+    fn generate_report(rng: &mut Rng) -> Vec<i64> {
+        let dir = *rng.choose(&[-1, 1]);
+        let mut level = rng.range(1..=30);
+        rng.vec(MIN_LEVELS..=8, |rng| {
+            let current = level;
+            level += if rng.chance(0.8) {
+                dir * rng.range(1..=3)
+            } else {
+                rng.range(-5..=5)
+            };
+            current
+        })
+    }
+
+    /// Reference for part two: safe as is, or after removing any single level.
+    fn brute_force_safe(report: &[i64]) -> bool {
+        is_safe_monotonic(report)
+            || (0..report.len()).any(|i| {
+                let mut removed = report.to_vec();
+                removed.remove(i);
+                is_safe_monotonic(&removed)
+            })
+    }
+
+    #[test]
+    fn line_safety_matches_brute_force() {
+        property::differential(
+            generate_report,
+            |report| {
+                property::shrink_vec(report, |&n| property::shrink_int(n, 0))
+                    .into_iter()
+                    .filter(|r| r.len() >= MIN_LEVELS)
+                    .collect()
+            },
+            |report| is_line_safe(report),
+            |report| brute_force_safe(report),
+        );
+    }
+
+    #[test]
+    fn explains_example() {
+        let rules = Rules {
+            max_removals: 1,
+            ..Rules::default()
+        };
+        // the reports of the puzzle description.
+        let input = "7 6 4 2 1\n1 2 7 8 9\n9 7 6 2 1\n1 3 2 4 5\n8 6 4 4 1\n1 3 6 7 9";
+        let verdicts: Vec<String> = input
+            .lines()
+            .map(|line| {
+                let nums: Vec<i64> = line
+                    .split_whitespace()
+                    .map(|n| n.parse().unwrap())
+                    .collect();
+                rules.check(&nums).to_string()
+            })
+            .collect();
+        assert_eq!(
+            verdicts,
+            [
+                "safe",
+                "unsafe: 2 -> 7 at index 1 is a step of 5, outside 1..=3",
+                "unsafe: 6 -> 2 at index 2 is a step of 4, outside 1..=3",
+                "safe after removing index 1",
+                "safe after removing index 2",
+                "safe",
+            ]
+        );
+        assert_eq!(
+            Rules::default().check(&[1, 3, 2, 4, 5]),
+            Verdict::Unsafe(Violation::Direction {
+                index: 1,
+                pair: (3, 2),
+                expected: Direction::Increasing,
+            })
+        );
+    }
 
     #[test]
     fn test_part_one() {
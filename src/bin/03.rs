@@ -1,42 +1,21 @@
-use regex::Regex;
-use std::option::Option;
+use advent_of_code::instructions::{self, InstructionSet, Spec};
 
 advent_of_code::solution!(3);
 
-pub fn part_one(input: &str) -> Option<u64> {
-    let re = Regex::new(r"mul\((?P<num_a>\d+),(?P<num_b>\d+)\)").unwrap();
-    let mut total: u64 = 0u64;
+/// `mul(a,b)` with arguments of 1 to 3 digits adds `a * b`.
+fn muls() -> InstructionSet {
+    InstructionSet::new().with(Spec::new("mul", 2, 1..=3), instructions::mul)
+}
 
-    for caps in re.captures_iter(input) {
-        let num_a: u64 = caps["num_a"].parse().unwrap();
-        let num_b: u64 = caps["num_b"].parse().unwrap();
-        total += num_a * num_b;
-    }
-    Some(total)
+pub fn part_one(input: &str) -> Option<u64> {
+    Some(muls().run(input).total)
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    let re = Regex::new(
-        r"((?P<enable>do\(\))|(?P<disable>don\'t\(\))|mul\((?P<num_a>\d+),(?P<num_b>\d+)\))",
-    )
-    .unwrap();
-    let mut total: u64 = 0u64;
-    let mut calc_enabled: bool = true;
-
-    for caps in re.captures_iter(input) {
-        match caps.get(1).unwrap().as_str() {
-            "do()" => calc_enabled = true,
-            "don't()" => calc_enabled = false,
-            _ => {
-                if calc_enabled {
-                    let num_a: u64 = caps["num_a"].parse().unwrap();
-                    let num_b: u64 = caps["num_b"].parse().unwrap();
-                    total += num_a * num_b;
-                }
-            }
-        }
-    }
-    Some(total)
+    let set = muls()
+        .with(Spec::nullary("do"), instructions::enable)
+        .with(Spec::nullary("don't"), instructions::disable);
+    Some(set.run(input).total)
 }
 
 #[cfg(test)]
@@ -1,20 +1,71 @@
-use advent_of_code::{count_word_in_str, count_x_word_in_str};
+use advent_of_code::grid::Grid;
+use advent_of_code::pattern::Matcher;
+use advent_of_code::visualize::{self, Color, Frame, Visualizer};
 
 advent_of_code::solution!(4);
 
+/// `XMAS` in a straight and a diagonal line, their orientations cover all 8 directions.
+const XMAS: [&str; 2] = ["XMAS", "X...\n.M..\n..A.\n...S"];
+
+/// Two `MAS` crossing in an X, its orientations cover both directions of both diagonals.
+const X_MAS: [&str; 1] = ["M.S\n.A.\nM.S"];
+
+/// Number of matches of `templates` in any orientation.
+fn count_matches(input: &str, templates: &[&str], name: &str) -> Option<u64> {
+    let grid = Grid::parse(input).ok()?;
+    let matches = Matcher::parse(templates).ok()?.find(&grid);
+
+    if visualize::requested() {
+        let hits: Vec<Vec<(usize, usize)>> = matches.iter().map(|m| m.cells.clone()).collect();
+        visualize_hits(input, name, &hits);
+    }
+
+    Some(matches.len() as u64)
+}
+
 pub fn part_one(input: &str) -> Option<u64> {
-    let result = count_word_in_str(&input, "XMAS").ok();
-    result.map::<u64, _>(|x| x as u64)
+    count_matches(input, &XMAS, "xmas")
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    let result = count_x_word_in_str(&input, "MAS").ok();
-    result.map::<u64, _>(|x| x as u64)
+    count_matches(input, &X_MAS, "x-mas")
+}
+
+/// Highlight every hit in turn: the current one in yellow, earlier ones in green.
+fn visualize_hits(input: &str, name: &str, hits: &[Vec<(usize, usize)>]) {
+    let rows: Vec<&[u8]> = input.lines().map(str::as_bytes).collect();
+    let mut frame = Frame::from_rows(&rows);
+    for r in 0..frame.height() {
+        for c in 0..frame.width() {
+            frame.paint(r, c, Color::Gray);
+        }
+    }
+
+    let mut visualizer = Visualizer::new(name);
+    let mut previous: Option<&Vec<(usize, usize)>> = None;
+
+    for (i, hit) in hits.iter().enumerate() {
+        for &(r, c) in previous.into_iter().flatten() {
+            frame.paint(r, c, Color::Green);
+        }
+        for &(r, c) in hit {
+            frame.paint(r, c, Color::Yellow);
+        }
+        frame.caption = format!("{} of {} hits", i + 1, hits.len());
+        visualizer.show(&frame, hit.first().copied());
+        previous = Some(hit);
+    }
+
+    for &(r, c) in previous.into_iter().flatten() {
+        frame.paint(r, c, Color::Green);
+    }
+    visualizer.finish(&frame, None);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::{count_word_in_str, count_x_word_in_str};
 
     #[test]
     fn overlapping_example() {
@@ -90,6 +141,19 @@ AAAAA";
         assert_eq!(count_x_word_in_str(input, "A").unwrap(), 0);
     }
 
+    #[test]
+    fn templates_match_word_search() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        assert_eq!(
+            part_one(&input),
+            Some(count_word_in_str(&input, "XMAS").unwrap() as u64)
+        );
+        assert_eq!(
+            part_two(&input),
+            Some(count_x_word_in_str(&input, "MAS").unwrap() as u64)
+        );
+    }
+
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
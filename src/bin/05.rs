@@ -1,40 +1,25 @@
-use std::collections::{BTreeSet, HashMap, HashSet};
+use std::collections::{HashMap, HashSet};
 
-advent_of_code::solution!(5);
+use advent_of_code::graph::{Cycle, Graph};
+use advent_of_code::parsing;
+use advent_of_code::template::Solution;
+
+advent_of_code::solution!(5, Day05);
 
 type Rule = (u32, u32);
 
-fn parse_input(s: &str) -> anyhow::Result<(Vec<Rule>, Vec<Vec<u32>>)> {
-    let (left, right) = s
-        .split_once("\n\n")
-        .ok_or_else(|| anyhow::anyhow!("expected a blank line separating rules and updates"))?;
-
-    let mut rules = Vec::new();
-    for line in left.lines().filter(|l| !l.trim().is_empty()) {
-        let (a, b) = line
-            .split_once('|')
-            .ok_or_else(|| anyhow::anyhow!("bad rule line: {line}"))?;
-        let a: u32 = a.trim().parse()?;
-        let b: u32 = b.trim().parse()?;
-        rules.push((a, b));
-    }
+fn parse_input(s: &str) -> parsing::Result<(Vec<Rule>, Vec<Vec<u32>>)> {
+    let [rules, updates] = parsing::sections_n::<2>(s)?;
 
-    let mut updates = Vec::new();
-    for line in right.lines().filter(|l| !l.trim().is_empty()) {
-        let row = line
-            .split(',')
-            .map(|t| t.trim().parse::<u32>())
-            .collect::<Result<Vec<_>, _>>()?;
-        // Optional: assert unique pages within an update (comment out if not needed)
-        // {
-        //     use std::collections::HashSet;
-        //     let mut set = HashSet::new();
-        //     if !row.iter().all(|&x| set.insert(x)) {
-        //         anyhow::bail!("duplicate page in update: {line}");
-        //     }
-        // }
-        updates.push(row);
-    }
+    let rules = rules
+        .iter()
+        .map(|line| line.record::<Rule>("|"))
+        .collect::<Result<_, _>>()?;
+
+    let updates = updates
+        .iter()
+        .map(|line| line.list::<u32>(","))
+        .collect::<Result<_, _>>()?;
 
     Ok((rules, updates))
 }
@@ -55,102 +40,94 @@ fn is_valid(update: &[u32], rules: &[Rule]) -> bool {
 }
 
 /// Reorders a single update so that it satisfies the rules.
-/// We perform a Kahn-style topological sort on the subgraph induced by the update's pages.
-/// To keep results deterministic and “AoC-like”, we break ties by the **original index** in the update.
-fn fix_update(update: &[u32], rules: &[Rule]) -> Option<Vec<u32>> {
-    // Presence set for this update
+/// We topologically sort the subgraph induced by the update's pages.
+/// To keep results deterministic and “AoC-like”, ties are broken by the **original index** in the
+/// update, which is the order the pages are added to the graph in.
+fn fix_update(update: &[u32], rules: &[Rule]) -> Result<Vec<u32>, Cycle<u32>> {
     let present: HashSet<u32> = update.iter().copied().collect();
 
-    // Record each page's original index to break ties deterministically.
-    let orig_idx: HashMap<u32, usize> = update.iter().enumerate().map(|(i, &v)| (v, i)).collect();
-
-    // Build subgraph: edges only for pages present in this update
-    let mut adj: HashMap<u32, Vec<u32>> = HashMap::new();
-    let mut indeg: HashMap<u32, usize> = HashMap::new();
-
-    for &p in &present {
-        adj.entry(p).or_default();
-        indeg.entry(p).or_insert(0);
+    let mut graph = Graph::new();
+    for &page in update {
+        graph.add_node(page);
     }
     for &(a, b) in rules {
         if present.contains(&a) && present.contains(&b) {
-            adj.entry(a).or_default().push(b);
-            *indeg.entry(b).or_insert(0) += 1;
+            graph.add_edge(a, b);
         }
     }
 
-    // Priority set of nodes with zero indegree, ordered by original index
-    let mut zeros: BTreeSet<(usize, u32)> = BTreeSet::new();
-    for (&node, &d) in &indeg {
-        if d == 0 {
-            zeros.insert((orig_idx[&node], node));
-        }
-    }
+    graph.topological_sort()
+}
 
-    let mut out = Vec::with_capacity(update.len());
-    let mut indeg_mut = indeg; // take ownership
-
-    while let Some((_, node)) = zeros.iter().next().cloned() {
-        zeros.take(&(orig_idx[&node], node));
-        out.push(node);
-        if let Some(neis) = adj.get(&node) {
-            for &v in neis {
-                let e = indeg_mut.get_mut(&v).unwrap();
-                *e -= 1;
-                if *e == 0 {
-                    zeros.insert((orig_idx[&v], v));
-                }
-            }
-        }
-    }
+pub struct Day05;
 
-    if out.len() == present.len() {
-        Some(out)
-    } else {
-        // Cycle or inconsistency detected — shouldn't happen in AoC inputs.
-        None
-    }
-}
+impl Solution for Day05 {
+    type Input = (Vec<Rule>, Vec<Vec<u32>>);
+    type Output1 = u64;
+    type Output2 = u64;
+    type Error = anyhow::Error;
 
-pub fn part_one(input: &str) -> Option<u64> {
-    let (rules, updates) = parse_input(&input).ok()?;
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(parse_input(input)?)
+    }
 
-    let mut sum: u64 = 0;
-    for u in updates {
-        if is_valid(&u, &rules) {
-            let mid = u[u.len() / 2] as u64;
-            sum += mid;
+    fn part_one((rules, updates): &Self::Input) -> anyhow::Result<u64> {
+        let mut sum: u64 = 0;
+        for u in updates {
+            if is_valid(u, rules) {
+                let mid = u[u.len() / 2] as u64;
+                sum += mid;
+            }
         }
+        Ok(sum)
     }
-    Some(sum)
-}
-
-pub fn part_two(input: &str) -> Option<u64> {
-    let (rules, updates) = parse_input(&input).ok()?;
 
-    let mut sum: u64 = 0;
-    for u in updates {
-        if !is_valid(&u, &rules) {
-            let fixed = fix_update(&u, &rules).expect("rules should be acyclic for the subset");
-            sum += fixed[fixed.len() / 2] as u64;
+    fn part_two((rules, updates): &Self::Input) -> anyhow::Result<u64> {
+        let mut sum: u64 = 0;
+        for u in updates {
+            if !is_valid(u, rules) {
+                let fixed = fix_update(u, rules).map_err(|cycle| {
+                    anyhow::anyhow!("rules are cyclic for update {u:?}: {cycle}")
+                })?;
+                sum += fixed[fixed.len() / 2] as u64;
+            }
         }
+        Ok(sum)
     }
-    Some(sum)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn reports_malformed_input() {
+        let error = Day05::parse("47|53\n97|x\n\n75,47").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 4: invalid digit found in string (\"x\")"
+        );
+        assert!(Day05::parse("47|53\n75,47").is_err());
+    }
+
+    #[test]
+    fn reports_cyclic_rules() {
+        let input = Day05::parse("1|2\n2|3\n3|1\n\n3,2,1").unwrap();
+        assert_eq!(
+            Day05::part_two(&input).unwrap_err().to_string(),
+            "rules are cyclic for update [3, 2, 1]: 3 -> 1 -> 2 -> 3"
+        );
+    }
+
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(143));
+        let result = Day05::solve_part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result.unwrap(), 143);
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(123));
+        let result = Day05::solve_part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result.unwrap(), 123);
     }
 }
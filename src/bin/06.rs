@@ -1,159 +1,186 @@
 advent_of_code::solution!(6);
 
-use std::collections::HashSet;
-
-type Grid = Vec<Vec<u8>>; // b'.' empty, b'#' obstacle
-type Pos = (i32, i32);
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Dir {
-    N = 0,
-    E = 1,
-    S = 2,
-    W = 3,
-}
-
-impl Dir {
-    #[inline]
-    fn right(self) -> Self {
-        match self {
-            Dir::N => Dir::E,
-            Dir::E => Dir::S,
-            Dir::S => Dir::W,
-            Dir::W => Dir::N,
-        }
-    }
-    #[inline]
-    fn delta(self) -> (i32, i32) {
-        match self {
-            Dir::N => (-1, 0),
-            Dir::E => (0, 1),
-            Dir::S => (1, 0),
-            Dir::W => (0, -1),
-        }
-    }
-}
+use advent_of_code::bitgrid::{BitGrid, DirBitGrid};
+use advent_of_code::cycle::{self, Run};
+use advent_of_code::geometry::{Dir4, Point};
+use advent_of_code::grid::Grid;
+use advent_of_code::visualize::{self, Color, Frame, Visualizer};
 
 #[derive(Debug)]
 enum SimOutcome {
-    Exited { visited: HashSet<Pos> }, // left the map
-    Looped,                           // repeated a (pos,dir) state
+    Exited { visited: BitGrid }, // left the map
+    Looped,                      // repeated a (pos,dir) state
 }
 
 #[derive(Debug)]
 struct Problem {
-    grid: Grid,
-    rows: i32,
-    cols: i32,
-    start: Pos,
-    dir0: Dir,
+    grid: Grid<u8>, // b'.' empty, b'#' obstacle
+    start: Point,
+    dir0: Dir4,
 }
 
 fn parse(input: &str) -> Problem {
-    let mut grid = Vec::new();
-    let mut start: Option<(Pos, Dir)> = None;
-
-    for (r, line) in input.lines().enumerate() {
-        let mut row = Vec::with_capacity(line.len());
-        for (c, ch) in line.bytes().enumerate() {
-            let (rr, cc) = (r as i32, c as i32);
-            match ch {
-                b'^' => {
-                    start = Some(((rr, cc), Dir::N));
-                    row.push(b'.');
-                }
-                b'>' => {
-                    start = Some(((rr, cc), Dir::E));
-                    row.push(b'.');
-                }
-                b'v' => {
-                    start = Some(((rr, cc), Dir::S));
-                    row.push(b'.');
-                }
-                b'<' => {
-                    start = Some(((rr, cc), Dir::W));
-                    row.push(b'.');
-                }
-                b'#' => row.push(b'#'),
-                b'.' => row.push(b'.'),
-                other => panic!("Unexpected char {other:?} at ({r},{c})"),
-            }
-        }
-        grid.push(row);
+    let mut grid = Grid::parse_bytes(input).expect("map should be rectangular");
+
+    let (r, c) = grid
+        .find(|b| b"^>v<".contains(b))
+        .expect("No start (^>v<) found");
+    let dir0 = Dir4::from_char(grid[(r, c)] as char).unwrap();
+    grid[(r, c)] = b'.';
+
+    if let Some((r, c)) = grid.find(|&b| b != b'.' && b != b'#') {
+        panic!("Unexpected char {:?} at ({r},{c})", grid[(r, c)]);
     }
 
-    let rows = grid.len() as i32;
-    let cols = grid.first().map(|r| r.len()).unwrap_or(0) as i32;
-    let (start_pos, dir0) = start.expect("No start (^>v<) found");
     Problem {
         grid,
-        rows,
-        cols,
-        start: start_pos,
+        start: Point::from_pos((r, c)),
         dir0,
     }
 }
 
 #[inline]
-fn in_bounds(p: Pos, rows: i32, cols: i32) -> bool {
-    p.0 >= 0 && p.0 < rows && p.1 >= 0 && p.1 < cols
-}
-
-#[inline]
-fn is_blocked(p: Pos, prob: &Problem, extra_block: Option<Pos>) -> bool {
+fn is_blocked(p: Point, prob: &Problem, extra_block: Option<Point>) -> bool {
     if let Some(b) = extra_block {
         if p == b {
             return true;
         }
     }
-    let (r, c) = (p.0 as usize, p.1 as usize);
-    prob.grid[r][c] == b'#'
+    prob.grid.get_point(p) == Some(&b'#')
 }
 
-/// Simulate one run. Returns Exited (with all visited cells) or Looped.
-/// - Turns right on obstacle.
-/// - Moves one cell at a time.
-/// - Counts a cell as visited **after moving into it**, but also includes the start.
-fn simulate(prob: &Problem, extra_block: Option<Pos>) -> SimOutcome {
-    let (rows, cols) = (prob.rows, prob.cols);
-    let mut pos = prob.start;
-    let mut dir = prob.dir0;
+/// The guard's position and facing.
+type State = (Point, Dir4);
 
-    let mut visited: HashSet<Pos> = HashSet::with_capacity(1 << 16);
-    visited.insert(pos);
-
-    // For loop detection we must include the facing
-    let mut seen: HashSet<(Pos, Dir)> = HashSet::with_capacity(1 << 16);
+/// One step of the guard, `None` once it leaves the map.
+/// - Turns right on obstacle (either original # or temporary extra_block), without moving.
+/// - Otherwise moves one cell ahead.
+fn step(prob: &Problem, extra_block: Option<Point>, &(pos, dir): &State) -> Option<State> {
+    let next = pos + dir.delta();
+    if !prob.grid.contains_point(next) {
+        None
+    } else if is_blocked(next, prob, extra_block) {
+        Some((pos, dir.right()))
+    } else {
+        Some((next, dir))
+    }
+}
 
+/// Walk straight ahead to the next obstacle and turn right there, `None` if the guard leaves
+/// the map on the way. Loops repeat these turns, so they are the only states worth comparing.
+fn turn(prob: &Problem, extra_block: Option<Point>, &(pos, dir): &State) -> Option<State> {
+    let mut pos = pos;
     loop {
-        // If we have seen the exact (pos,dir), it’s a loop
-        if !seen.insert((pos, dir)) {
-            return SimOutcome::Looped;
+        let next = pos + dir.delta();
+        if is_blocked(next, prob, extra_block) {
+            return Some((pos, dir.right()));
+        }
+        if !prob.grid.contains_point(next) {
+            return None;
         }
+        pos = next;
+    }
+}
+
+/// Simulate one run. Returns Exited (with all visited cells) or Looped.
+/// Counts a cell as visited **after moving into it**, but also includes the start.
+/// For loop detection we must include the facing, so the whole state is compared.
+fn simulate(prob: &Problem, extra_block: Option<Point>) -> SimOutcome {
+    let mut visited = BitGrid::like(&prob.grid);
+    visited.insert_point(prob.start);
 
-        let (dr, dc) = dir.delta();
-        let next = (pos.0 + dr, pos.1 + dc);
+    let run = cycle::run((prob.start, prob.dir0), |state| {
+        let next = step(prob, extra_block, state)?;
+        visited.insert_point(next.0);
+        Some(next)
+    });
 
-        // Exiting the map?
-        if !in_bounds(next, rows, cols) {
-            return SimOutcome::Exited { visited };
+    match run {
+        Run::Halted { .. } => SimOutcome::Exited { visited },
+        Run::Looped { .. } => SimOutcome::Looped,
+    }
+}
+
+/* ----------------------- visualization ----------------------- */
+
+fn base_frame(prob: &Problem) -> Frame {
+    let rows: Vec<&[u8]> = prob.grid.rows().collect();
+    let mut frame = Frame::from_rows(&rows);
+    for ((r, c), &b) in prob.grid.iter() {
+        if b == b'#' {
+            frame.paint(r, c, Color::Gray);
+        } else {
+            frame.set(r, c, ' ', Color::Default);
         }
+    }
+    frame
+}
 
-        // Obstacle (either original # or temporary extra_block)?
-        if is_blocked(next, prob, extra_block) {
+/// Animate the guard walking the map, leaving a trail of visited cells.
+fn visualize_walk(prob: &Problem) {
+    let mut visualizer = Visualizer::new("walk");
+    let mut frame = base_frame(prob);
+    let (mut pos, mut dir) = (prob.start, prob.dir0);
+    let mut visited = 1;
+
+    loop {
+        let focus = pos.to_pos().unwrap();
+        frame.set(focus.0, focus.1, dir.symbol(), Color::Yellow);
+        frame.caption = format!("Part 1: guard walk, {visited} cells visited");
+        visualizer.show(&frame, Some(focus));
+
+        let next = pos + dir.delta();
+        if !prob.grid.contains_point(next) {
+            frame.set(focus.0, focus.1, '·', Color::Green);
+            visualizer.finish(&frame, Some(focus));
+            return;
+        }
+        if is_blocked(next, prob, None) {
             dir = dir.right();
-            continue; // turn but do not move
+            continue;
         }
 
-        // Move ahead
+        frame.set(focus.0, focus.1, '·', Color::Green);
+        if frame
+            .get(next.y as usize, next.x as usize)
+            .is_some_and(|c| c.ch == ' ')
+        {
+            visited += 1;
+        }
         pos = next;
-        visited.insert(pos);
     }
 }
 
+/// Reveal the obstacles that make the guard loop, one at a time, on top of the original path.
+fn visualize_loops(prob: &Problem, path: &BitGrid, obstacles: &[Point]) {
+    let mut visualizer = Visualizer::new("loops");
+    let mut frame = base_frame(prob);
+    for (r, c) in path.iter() {
+        frame.set(r, c, '·', Color::Green);
+    }
+    let (r, c) = prob.start.to_pos().unwrap();
+    frame.set(r, c, prob.dir0.symbol(), Color::Yellow);
+
+    let mut focus = None;
+    for (i, (r, c)) in obstacles.iter().filter_map(|p| p.to_pos()).enumerate() {
+        if let Some((fr, fc)) = focus {
+            frame.paint(fr, fc, Color::Magenta);
+        }
+        focus = Some((r, c));
+        frame.set(r, c, 'O', Color::Red);
+        frame.caption = format!("Part 2: {} loop-causing obstacles", i + 1);
+        visualizer.show(&frame, focus);
+    }
+
+    visualizer.finish(&frame, focus);
+}
+
 /// Part 1: number of distinct cells visited before exiting.
 fn part_one(input: &str) -> Option<u64> {
     let prob = parse(input);
+    if visualize::requested() {
+        visualize_walk(&prob);
+    }
     match simulate(&prob, None) {
         SimOutcome::Exited { visited } => Some(visited.len() as u64),
         SimOutcome::Looped => panic!("Unexpected loop in part 1"),
@@ -164,36 +191,162 @@ fn part_one(input: &str) -> Option<u64> {
 /// Pruned: only consider placing on cells from the original path (excluding start).
 fn part_two(input: &str) -> Option<u64> {
     let prob = parse(input);
-    let path_cells: HashSet<Pos> = match simulate(&prob, None) {
+    let path_cells: BitGrid = match simulate(&prob, None) {
         SimOutcome::Exited { visited } => visited,
         SimOutcome::Looped => panic!("Unexpected loop in baseline run"),
     };
 
-    let mut count = 0usize;
+    let mut obstacles: Vec<Point> = Vec::new();
+    // reused for every candidate, clearing only resets what the previous walk touched.
+    let mut seen = DirBitGrid::like(&prob.grid);
 
-    for &cand in &path_cells {
+    for cand in path_cells.iter().map(Point::from_pos) {
         if cand == prob.start {
             continue;
         } // cannot place on start
           // skip if original map already has a wall (shouldn’t happen since path excludes walls)
-        let (r, c) = (cand.0 as usize, cand.1 as usize);
-        if prob.grid[r][c] == b'#' {
+        if is_blocked(cand, &prob, None) {
             continue;
         }
 
-        match simulate(&prob, Some(cand)) {
-            SimOutcome::Looped => count += 1,
-            SimOutcome::Exited { .. } => {} // not a looper
+        seen.clear();
+        if cycle::loops_with(
+            (prob.start, prob.dir0),
+            |state| turn(&prob, Some(cand), state),
+            |&(pos, dir)| seen.insert_point(pos, dir),
+        ) {
+            obstacles.push(cand);
         }
     }
 
-    Some(count as u64)
+    if visualize::requested() {
+        visualize_loops(&prob, &path_cells, &obstacles);
+    }
+
+    Some(obstacles.len() as u64)
 }
 
 /* ----------------------- tiny smoke tests ----------------------- */
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::property::{self, Rng};
+
+    /// Random map with obstacles and a guard somewhere in the open.
+    fn generate_map(rng: &mut Rng) -> Vec<Vec<u8>> {
+        let (rows, cols) = (rng.usize(2..=8), rng.usize(2..=8));
+        let density = rng.range(5..=35) as f64 / 100.0;
+        let mut map: Vec<Vec<u8>> = (0..rows)
+            .map(|_| {
+                (0..cols)
+                    .map(|_| if rng.chance(density) { b'#' } else { b'.' })
+                    .collect()
+            })
+            .collect();
+        let (r, c) = (rng.usize(0..=rows - 1), rng.usize(0..=cols - 1));
+        map[r][c] = *rng.choose(b"^>v<");
+        map
+    }
+
+    fn render(map: &[Vec<u8>]) -> String {
+        map.iter()
+            .map(|row| String::from_utf8_lossy(row).into_owned())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Shrink by clearing obstacles and dropping outer rows and columns without the guard.
+    fn shrink_map(map: &[Vec<u8>]) -> Vec<Vec<Vec<u8>>> {
+        let is_guard = |b: &u8| b"^>v<".contains(b);
+        let mut out = vec![];
+
+        for (i, row) in [0, map.len() - 1].into_iter().map(|i| (i, &map[i])) {
+            if map.len() > 1 && !row.iter().any(is_guard) {
+                let mut smaller = map.to_vec();
+                smaller.remove(i);
+                out.push(smaller);
+            }
+        }
+        for j in [0, map[0].len() - 1] {
+            if map[0].len() > 1 && !map.iter().any(|row| is_guard(&row[j])) {
+                out.push(
+                    map.iter()
+                        .map(|row| [&row[..j], &row[j + 1..]].concat())
+                        .collect(),
+                );
+            }
+        }
+        for (r, row) in map.iter().enumerate() {
+            for (c, &b) in row.iter().enumerate() {
+                if b == b'#' {
+                    let mut smaller = map.to_vec();
+                    smaller[r][c] = b'.';
+                    out.push(smaller);
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Reference walk: step by step, a loop is any walk longer than the number of states.
+    /// Returns the number of visited cells if the guard leaves the map.
+    fn brute_force_walk(prob: &Problem, extra_block: Option<Point>) -> Option<usize> {
+        let (mut pos, mut dir) = (prob.start, prob.dir0);
+        let mut visited = prob.grid.map(|_| false);
+        *visited.get_point_mut(pos).unwrap() = true;
+
+        for _ in 0..=4 * prob.grid.len() {
+            let next = pos + dir.delta();
+            let Some(&cell) = prob.grid.get_point(next) else {
+                return Some(visited.positions(|&v| v).count());
+            };
+            if cell == b'#' || Some(next) == extra_block {
+                dir = dir.right();
+            } else {
+                pos = next;
+                *visited.get_point_mut(pos).unwrap() = true;
+            }
+        }
+
+        None
+    }
+
+    /// Reference for both parts: try an obstacle on every free cell, not only on the path.
+    fn brute_force(map: &[Vec<u8>]) -> Option<(u64, u64)> {
+        let prob = parse(&render(map));
+        let visited = brute_force_walk(&prob, None)?;
+        let loops = prob
+            .grid
+            .positions(|&b| b == b'.')
+            .map(Point::from_pos)
+            .filter(|&p| p != prob.start && brute_force_walk(&prob, Some(p)).is_none())
+            .count();
+        Some((visited as u64, loops as u64))
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        property::check(
+            generate_map,
+            |map| shrink_map(map),
+            |map| {
+                // maps where the guard never leaves are not valid puzzle inputs.
+                let Some(expected) = brute_force(map) else {
+                    return Ok(());
+                };
+                let input = render(map);
+                let actual = (part_one(&input).unwrap(), part_two(&input).unwrap());
+                if actual == expected {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "expected {expected:?}, got {actual:?} for\n{input}"
+                    ))
+                }
+            },
+        );
+    }
 
     #[test]
     fn test_part_one() {
@@ -1,33 +1,47 @@
-use std::collections::HashSet;
+use advent_of_code::expr::{Operator, Solver};
+use advent_of_code::parsing::{self, ParseError, Span};
 
-advent_of_code::solution!(7);
+advent_of_code::solution!(
+    7,
+    [bitmask: part_one, dfs: part_one_dfs, all: part_one_all],
+    [dfs: part_two, all: part_two_all]
+);
 
 pub fn part_one(input: &str) -> Option<u64> {
-    let puzzles = parse_input(input).expect("unable to parse input file");
-
-    let mut result = 0u128;
+    sum_solvable(input, solvable)
+}
 
-    for (_, puzzle) in puzzles.iter().enumerate() {
-        if solvable(&puzzle.1, puzzle.0) {
-            result += puzzle.0;
-        } else {
-            continue;
-        }
-    }
+/// Part one via the pruning DFS, restricted to `+` and `*`.
+pub fn part_one_dfs(input: &str) -> Option<u64> {
+    let solver = solver(false);
+    sum_solvable(input, |nums, target| solver.first(nums, target).is_some())
+}
 
-    Some(result.try_into().unwrap())
+/// Part one by enumerating every solution with the bitmask solver.
+pub fn part_one_all(input: &str) -> Option<u64> {
+    sum_solvable(input, |nums, target| !solve_all(nums, target).is_empty())
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
+    let solver = solver(true);
+    sum_solvable(input, |nums, target| solver.first(nums, target).is_some())
+}
+
+/// Part two by enumerating every solution with the DFS solver.
+pub fn part_two_all(input: &str) -> Option<u64> {
+    let solver = solver(true);
+    sum_solvable(input, |nums, target| !solver.all(nums, target).is_empty())
+}
+
+/// Sum of the targets of all equations accepted by `solvable`.
+fn sum_solvable(input: &str, solvable: impl Fn(&[u128], u128) -> bool) -> Option<u64> {
     let puzzles = parse_input(input).expect("unable to parse input file");
 
     let mut result = 0u128;
 
-    for (_, puzzle) in puzzles.iter().enumerate() {
-        if solvable_dfs(&puzzle.1, puzzle.0) {
+    for puzzle in puzzles.iter() {
+        if solvable(&puzzle.1, puzzle.0) {
             result += puzzle.0;
-        } else {
-            continue;
         }
     }
 
@@ -35,33 +49,24 @@ pub fn part_two(input: &str) -> Option<u64> {
 }
 
 /// Parse into target values & factors
-pub fn parse_input(input: &str) -> Result<Vec<(u128, Vec<u128>)>, String> {
-    let lines: Vec<&str> = input.lines().collect();
+pub fn parse_input(input: &str) -> parsing::Result<Vec<(u128, Vec<u128>)>> {
+    let lines: Vec<Span> = parsing::lines(input).collect();
     if lines.is_empty() {
-        return Err("empty input".into());
-    }
-
-    let mut result: Vec<(u128, Vec<u128>)> = vec![];
-    for (_, line) in lines.iter().enumerate() {
-        result.push(parse_line(line).expect("Unable to parse line"));
+        return Err(ParseError {
+            line: 1,
+            column: 1,
+            message: "empty input".into(),
+        });
     }
-    Ok(result)
-}
-
-fn parse_line(line: &str) -> Option<(u128, Vec<u128>)> {
-    // Split at the colon
-    let (left, right) = line.split_once(':')?; // returns Option<(str,str)>
-
-    // Parse the left side as the target number
-    let target = left.trim().parse::<u128>().ok()?;
-
-    // Split the right side by whitespace and parse each number
-    let nums: Vec<u128> = right
-        .split_whitespace()
-        .filter_map(|s| s.parse::<u128>().ok())
-        .collect();
 
-    Some((target, nums))
+    lines
+        .iter()
+        .map(|line| {
+            // Split at the colon: target on the left, numbers on the right
+            let (target, nums) = line.split_once(":")?;
+            Ok((target.parse()?, nums.ints()?))
+        })
+        .collect()
 }
 
 /// Evaluate left-to-right using a bit-mask where:
@@ -72,9 +77,9 @@ fn eval_mask(nums: &[u128], mask: u64) -> u128 {
     for i in 0..(nums.len() - 1) {
         let next = nums[i + 1];
         if ((mask >> i) & 1) == 1 {
-            acc = acc * next; // '*'
+            acc *= next; // '*'
         } else {
-            acc = acc + next; // '+'
+            acc += next; // '+'
         }
     }
     acc
@@ -114,7 +119,7 @@ fn solve_first(nums: &[u128], target: u128) -> Option<String> {
 }
 
 /// Return all solutions as formatted strings (possibly empty).
-fn _solve_all(nums: &[u128], target: u128) -> Vec<String> {
+fn solve_all(nums: &[u128], target: u128) -> Vec<String> {
     if nums.is_empty() {
         return vec![];
     }
@@ -143,181 +148,31 @@ fn solvable(nums: &[u128], target: u128) -> bool {
     solve_first(nums, target).is_some()
 }
 
-#[derive(Copy, Clone, Debug)]
-enum Op {
-    Add,
-    Mul,
-    Concat,
-}
-
-#[inline]
-fn digits10(mut x: u128) -> u32 {
-    if x == 0 {
-        return 1;
-    }
-    let mut d = 0;
-    while x > 0 {
-        d += 1;
-        x /= 10;
-    }
-    d
-}
-
-#[inline]
-fn pow10_u128(d: u32) -> u128 {
-    let mut p = 1u128;
-    for _ in 0..d {
-        p = p.saturating_mul(10);
-    }
-    p
-}
-
-#[inline]
-fn concat_u128(a: u128, b: u128) -> Option<u128> {
-    let p = pow10_u128(digits10(b));
-    a.checked_mul(p)?.checked_add(b)
-}
-
-#[inline]
-fn apply_op(acc: u128, op: Op, next: u128) -> Option<u128> {
-    match op {
-        Op::Add => acc.checked_add(next),
-        Op::Mul => acc.checked_mul(next),
-        Op::Concat => concat_u128(acc, next),
-    }
-}
-
-fn render(nums: &[u128], ops: &[Op]) -> String {
-    let mut s = nums[0].to_string();
-    for (i, op) in ops.iter().enumerate() {
-        let sym = match op {
-            Op::Add => " + ",
-            Op::Mul => " * ",
-            Op::Concat => " || ",
-        };
-        s.push_str(sym);
-        s.push_str(&nums[i + 1].to_string());
+/// The operators of day 7, evaluated left to right, with `||` for part two.
+fn solver(concat: bool) -> Solver<u128> {
+    let mut operators = vec![Operator::add(), Operator::mul()];
+    if concat {
+        operators.push(Operator::concat());
     }
-    s
+    // all numbers are positive, so no operator makes the value smaller.
+    Solver::new(operators).non_decreasing()
 }
 
 /// First solution via DFS with pruning & memo (AoC-style: all nums > 0).
 pub fn solve_first_dfs(nums: &[u128], target: u128) -> Option<String> {
-    if nums.is_empty() {
-        return None;
-    }
-    if nums.len() == 1 {
-        return (nums[0] == target).then(|| nums[0].to_string());
-    }
-
-    // Memo of dead states: (index, acc) → no solution from here
-    let mut dead: HashSet<(usize, u128)> = HashSet::new();
-    let mut ops: Vec<Op> = Vec::with_capacity(nums.len().saturating_sub(1));
-
-    fn dfs(
-        i: usize,
-        acc: u128,
-        nums: &[u128],
-        target: u128,
-        dead: &mut HashSet<(usize, u128)>,
-        ops: &mut Vec<Op>,
-    ) -> bool {
-        if i == nums.len() {
-            return acc == target;
-        }
-
-        // Prune: all nums > 0 → any op strictly increases acc
-        if acc > target {
-            return false;
-        }
-
-        let key = (i, acc);
-        if dead.contains(&key) {
-            return false;
-        }
-
-        // Try ops in an arbitrary order; you can reorder if you like.
-        for &op in [Op::Add, Op::Mul, Op::Concat].iter() {
-            if let Some(next_acc) = apply_op(acc, op, nums[i]) {
-                ops.push(op);
-                if dfs(i + 1, next_acc, nums, target, dead, ops) {
-                    return true;
-                }
-                ops.pop();
-            }
-        }
-
-        dead.insert(key);
-        false
-    }
-
-    let ok = dfs(1, nums[0], nums, target, &mut dead, &mut ops);
-    ok.then(|| render(nums, &ops))
+    let solver = solver(true);
+    let ops = solver.first(nums, target)?;
+    Some(solver.render(nums, &ops))
 }
 
-/// All solutions via DFS (same pruning, memo limited to “first failure” cache).
+/// All solutions via DFS, with the same pruning.
 pub fn solve_all_dfs(nums: &[u128], target: u128) -> Vec<String> {
-    if nums.is_empty() {
-        return vec![];
-    }
-    if nums.len() == 1 {
-        return if nums[0] == target {
-            vec![nums[0].to_string()]
-        } else {
-            vec![]
-        };
-    }
-
-    // For collecting all, we can still use a “dead” memo: if a (i,acc) has no solution paths,
-    // we can prune whenever we hit it again.
-    let mut dead: HashSet<(usize, u128)> = HashSet::new();
-    let mut ops: Vec<Op> = Vec::with_capacity(nums.len().saturating_sub(1));
-    let mut all: Vec<String> = Vec::new();
-
-    fn dfs_all(
-        i: usize,
-        acc: u128,
-        nums: &[u128],
-        target: u128,
-        dead: &mut HashSet<(usize, u128)>,
-        ops: &mut Vec<Op>,
-        out: &mut Vec<String>,
-    ) -> bool {
-        if i == nums.len() {
-            if acc == target {
-                out.push(render(nums, ops));
-                return true;
-            }
-            return false;
-        }
-
-        if acc > target {
-            return false;
-        }
-
-        let key = (i, acc);
-        if dead.contains(&key) {
-            return false;
-        }
-
-        let mut found_any = false;
-        for &op in [Op::Add, Op::Mul, Op::Concat].iter() {
-            if let Some(next_acc) = apply_op(acc, op, nums[i]) {
-                ops.push(op);
-                let had = dfs_all(i + 1, next_acc, nums, target, dead, ops, out);
-                found_any |= had;
-                ops.pop();
-            }
-        }
-
-        if !found_any {
-            dead.insert(key);
-        }
-        found_any
-    }
-
-    dfs_all(1, nums[0], nums, target, &mut dead, &mut ops, &mut all);
-    all
+    let solver = solver(true);
+    solver
+        .all(nums, target)
+        .iter()
+        .map(|ops| solver.render(nums, ops))
+        .collect()
 }
 
 pub fn solvable_dfs(nums: &[u128], target: u128) -> bool {
@@ -328,6 +183,78 @@ pub fn solvable_dfs(nums: &[u128], target: u128) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::math;
+    use advent_of_code::property::{self, Rng};
+
+    type Equation = (u128, Vec<u128>);
+
+    /// `+`, `*` and `||`, independent of the solver under test.
+    const OPS: [fn(u128, u128) -> Option<u128>; 3] =
+        [u128::checked_add, u128::checked_mul, math::checked_concat];
+
+    /// Random equation. Half of the targets are reachable by construction.
+    fn generate_equation(rng: &mut Rng) -> Equation {
+        let nums: Vec<u128> = rng.vec(1..=6, |rng| rng.range(1..=20) as u128);
+        let target = if rng.chance(0.5) {
+            nums[1..]
+                .iter()
+                .fold(nums[0], |acc, &n| rng.choose(&OPS)(acc, n).unwrap())
+        } else {
+            rng.range(1..=5000) as u128
+        };
+        (target, nums)
+    }
+
+    /// Shrink numbers towards 1, since solutions assume positive numbers.
+    fn shrink_equation((target, nums): &Equation) -> Vec<Equation> {
+        let mut out: Vec<Equation> = property::shrink_vec(nums, |&n| {
+            property::shrink_int(n as i64, 1)
+                .into_iter()
+                .map(|n| n as u128)
+                .collect()
+        })
+        .into_iter()
+        .filter(|nums| !nums.is_empty())
+        .map(|nums| (*target, nums))
+        .collect();
+        out.extend(
+            property::shrink_int(*target as i64, 0)
+                .into_iter()
+                .map(|t| (t as u128, nums.clone())),
+        );
+        out
+    }
+
+    /// Reference: evaluate every combination of operators left to right.
+    fn brute_force(nums: &[u128], target: u128, concat: bool) -> bool {
+        let ops = if concat { &OPS[..] } else { &OPS[..2] };
+        let combinations = ops.len().pow(nums.len() as u32 - 1);
+        (0..combinations).any(|mut combination| {
+            nums[1..]
+                .iter()
+                .try_fold(nums[0], |acc, &n| {
+                    let op = ops[combination % ops.len()];
+                    combination /= ops.len();
+                    op(acc, n)
+                })
+                .is_some_and(|result| result == target)
+        })
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        property::differential(
+            generate_equation,
+            shrink_equation,
+            |(target, nums)| (solvable(nums, *target), solvable_dfs(nums, *target)),
+            |(target, nums)| {
+                (
+                    brute_force(nums, *target, false),
+                    brute_force(nums, *target, true),
+                )
+            },
+        );
+    }
 
     #[test]
     fn base_two_ops_example() {
@@ -371,4 +298,12 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(11387));
     }
+
+    #[test]
+    fn variants_agree() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        assert_eq!(part_one_dfs(&input), Some(3749));
+        assert_eq!(part_one_all(&input), Some(3749));
+        assert_eq!(part_two_all(&input), Some(11387));
+    }
 }
@@ -1,97 +1,102 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 
-advent_of_code::solution!(8);
+use advent_of_code::bitgrid::BitGrid;
+use advent_of_code::geometry::Point;
+use advent_of_code::grid::Grid;
+use advent_of_code::visualize::{self, Color, Frame, Visualizer};
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
-struct Point {
-    x: i32,
-    y: i32,
-}
+advent_of_code::solution!(8);
 
 pub fn part_one(input: &str) -> Option<u64> {
-    let grid: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
-    let height = grid.len() as i32;
-    let width = grid[0].len() as i32;
-
-    // Collect antenna locations by frequency
-    let mut antennas: HashMap<char, Vec<Point>> = HashMap::new();
-    for (y, row) in grid.iter().enumerate() {
-        for (x, &ch) in row.iter().enumerate() {
-            if ch != '.' {
-                antennas.entry(ch).or_default().push(Point {
-                    x: x as i32,
-                    y: y as i32,
-                });
-            }
-        }
-    }
+    let grid = Grid::parse(input).ok()?;
+    let antennas = antennas(&grid);
 
-    let mut antinodes = HashSet::new();
-    for (_freq, points) in &antennas {
+    let mut antinodes = BitGrid::like(&grid);
+    for points in antennas.values() {
         for (i, &a) in points.iter().enumerate() {
             for &b in &points[i + 1..] {
-                let dx = a.x - b.x;
-                let dy = a.y - b.y;
-                let p1 = Point {
-                    x: a.x + dx,
-                    y: a.y + dy,
-                };
-                let p2 = Point {
-                    x: b.x - dx,
-                    y: b.y - dy,
-                };
-                for p in [p1, p2] {
-                    if (0..width).contains(&p.x) && (0..height).contains(&p.y) {
-                        antinodes.insert(p);
+                let d = a - b;
+                for p in [a + d, b - d] {
+                    if grid.contains_point(p) {
+                        antinodes.insert_point(p);
                     }
                 }
             }
         }
     }
 
+    if visualize::requested() {
+        visualize_antinodes(&grid, "antinodes", &antinodes);
+    }
+
     Some(antinodes.len().try_into().unwrap())
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    let grid: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
-    let height = grid.len() as i32;
-    let width = grid[0].len() as i32;
-
-    // Collect antenna locations by frequency
-    let mut antennas: HashMap<char, Vec<Point>> = HashMap::new();
-    for (y, row) in grid.iter().enumerate() {
-        for (x, &ch) in row.iter().enumerate() {
-            if ch != '.' {
-                antennas.entry(ch).or_default().push(Point {
-                    x: x as i32,
-                    y: y as i32,
-                });
-            }
-        }
-    }
+    let grid = Grid::parse(input).ok()?;
+    let antennas = antennas(&grid);
 
-    let mut all_antinodes = HashSet::new();
-    for (_freq, points) in &antennas {
+    let mut all_antinodes = BitGrid::like(&grid);
+    for points in antennas.values() {
         for (i, &a) in points.iter().enumerate() {
             for &b in &points[i + 1..] {
-                let dx = a.x - b.x;
-                let dy = a.y - b.y;
+                let d = a - b;
 
                 // Step in both directions until outside the grid
                 for n in -1000..=1000 {
-                    let x = a.x + n * dx;
-                    let y = a.y + n * dy;
-                    if (0..width).contains(&x) && (0..height).contains(&y) {
-                        all_antinodes.insert(Point { x, y });
+                    let p = a + d * n;
+                    if grid.contains_point(p) {
+                        all_antinodes.insert_point(p);
                     }
                 }
             }
         }
     }
 
+    if visualize::requested() {
+        visualize_antinodes(&grid, "harmonics", &all_antinodes);
+    }
+
     Some(all_antinodes.len().try_into().unwrap())
 }
 
+/// Antenna locations by frequency.
+fn antennas(grid: &Grid<char>) -> HashMap<char, Vec<Point>> {
+    let mut antennas: HashMap<char, Vec<Point>> = HashMap::new();
+    for ((y, x), &ch) in grid.iter() {
+        if ch != '.' {
+            antennas
+                .entry(ch)
+                .or_default()
+                .push(Point::from_pos((y, x)));
+        }
+    }
+    antennas
+}
+
+/// Reveal the antinodes row by row on top of the antennas.
+fn visualize_antinodes(grid: &Grid<char>, name: &str, antinodes: &BitGrid) {
+    let mut frame = Frame::new(grid.width(), grid.height());
+    for ((y, x), &ch) in grid.iter() {
+        if ch != '.' {
+            frame.set(y, x, ch, Color::Cyan);
+        }
+    }
+
+    let mut visualizer = Visualizer::new(name);
+    for (i, (y, x)) in antinodes.iter().enumerate() {
+        match frame.get(y, x) {
+            // antennas keep their frequency, but are marked as antinodes too.
+            Some(cell) if cell.ch != ' ' => frame.set(y, x, cell.ch, Color::Red),
+            _ => frame.set(y, x, '#', Color::Red),
+        }
+        frame.caption = format!("{} of {} antinodes", i + 1, antinodes.len());
+        visualizer.show(&frame, Some((y, x)));
+    }
+
+    visualizer.finish(&frame, None);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,8 @@
+//! Runs every scaffolded day in a single process.
+//! The list of days is generated by `build.rs`, see `template::registry`.
+
+include!(concat!(env!("OUT_DIR"), "/registry.rs"));
+
+fn main() {
+    advent_of_code::template::registry::main(SOLUTIONS);
+}
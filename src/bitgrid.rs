@@ -0,0 +1,308 @@
+//! Dense sets of grid positions, one bit per cell.
+//!
+//! Faster and smaller than a `HashSet` of positions whenever a good part of the grid is visited.
+//! Sets can be cleared cheaply and reused, e.g. across the simulations of a brute force search:
+//! clearing only touches the words that were written to since the last clear.
+use crate::{
+    geometry::{Dir4, Point},
+    grid::Grid,
+};
+
+/// A set of indices below a fixed capacity.
+#[derive(Clone, Debug, Default)]
+pub struct BitSet {
+    words: Vec<u64>,
+    len: usize,
+    /// Words that were set since the last clear.
+    dirty: Vec<usize>,
+}
+
+impl BitSet {
+    pub fn new(capacity: usize) -> Self {
+        BitSet {
+            words: vec![0; capacity.div_ceil(64)],
+            len: 0,
+            dirty: vec![],
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.words.len() * 64
+    }
+
+    /// Add `index`, returns whether it was not in the set yet. Panics if it exceeds the capacity.
+    pub fn insert(&mut self, index: usize) -> bool {
+        let (word, bit) = (index / 64, 1 << (index % 64));
+        let old = self.words[word];
+        if old & bit != 0 {
+            return false;
+        }
+        if old == 0 {
+            self.dirty.push(word);
+        }
+        self.words[word] = old | bit;
+        self.len += 1;
+        true
+    }
+
+    /// Remove `index`, returns whether it was in the set.
+    pub fn remove(&mut self, index: usize) -> bool {
+        let (word, bit) = (index / 64, 1 << (index % 64));
+        let present = self.words.get(word).is_some_and(|w| w & bit != 0);
+        if present {
+            self.words[word] &= !bit;
+            self.len -= 1;
+        }
+        present
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        self.words
+            .get(index / 64)
+            .is_some_and(|w| w & (1 << (index % 64)) != 0)
+    }
+
+    /// Number of indices in the set.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        for word in self.dirty.drain(..) {
+            self.words[word] = 0;
+        }
+        self.len = 0;
+    }
+
+    /// Indices in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut rest = word;
+            std::iter::from_fn(move || {
+                (rest != 0).then(|| {
+                    let bit = rest.trailing_zeros() as usize;
+                    rest &= rest - 1;
+                    i * 64 + bit
+                })
+            })
+        })
+    }
+}
+
+/// A set of `(row, col)` positions of a `width` × `height` grid.
+#[derive(Clone, Debug)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    bits: BitSet,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        BitGrid {
+            width,
+            height,
+            bits: BitSet::new(width * height),
+        }
+    }
+
+    /// An empty set with the size of `grid`.
+    pub fn like<T>(grid: &Grid<T>) -> Self {
+        BitGrid::new(grid.width(), grid.height())
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index(&self, (row, col): (usize, usize)) -> Option<usize> {
+        (row < self.height && col < self.width).then_some(row * self.width + col)
+    }
+
+    fn point_index(&self, p: Point) -> Option<usize> {
+        self.index(p.to_pos()?)
+    }
+
+    /// Add `pos`, returns whether it was not in the set yet. Panics if `pos` is outside.
+    pub fn insert(&mut self, pos: (usize, usize)) -> bool {
+        let index = self.index(pos).expect("position outside of the grid");
+        self.bits.insert(index)
+    }
+
+    pub fn remove(&mut self, pos: (usize, usize)) -> bool {
+        self.index(pos).is_some_and(|i| self.bits.remove(i))
+    }
+
+    pub fn contains(&self, pos: (usize, usize)) -> bool {
+        self.index(pos).is_some_and(|i| self.bits.contains(i))
+    }
+
+    /// Like [`BitGrid::insert`], for the point `(x, y)` at `(row, col) = (y, x)`.
+    pub fn insert_point(&mut self, p: Point) -> bool {
+        let index = self.point_index(p).expect("point outside of the grid");
+        self.bits.insert(index)
+    }
+
+    pub fn contains_point(&self, p: Point) -> bool {
+        self.point_index(p).is_some_and(|i| self.bits.contains(i))
+    }
+
+    /// Number of positions in the set.
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.clear();
+    }
+
+    /// Positions in reading order.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.bits.iter().map(|i| (i / self.width, i % self.width))
+    }
+}
+
+/// A set of `(position, direction)` states on a `width` × `height` grid, e.g. to detect a
+/// walker that passes the same cell in the same direction twice.
+#[derive(Clone, Debug)]
+pub struct DirBitGrid {
+    width: usize,
+    height: usize,
+    bits: BitSet,
+}
+
+impl DirBitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        DirBitGrid {
+            width,
+            height,
+            bits: BitSet::new(width * height * 4),
+        }
+    }
+
+    /// An empty set with the size of `grid`.
+    pub fn like<T>(grid: &Grid<T>) -> Self {
+        DirBitGrid::new(grid.width(), grid.height())
+    }
+
+    fn index(&self, (row, col): (usize, usize), dir: Dir4) -> Option<usize> {
+        (row < self.height && col < self.width)
+            .then_some((row * self.width + col) * 4 + dir.index())
+    }
+
+    /// Add the state, returns whether it was not in the set yet. Panics if `pos` is outside.
+    pub fn insert(&mut self, pos: (usize, usize), dir: Dir4) -> bool {
+        let index = self.index(pos, dir).expect("position outside of the grid");
+        self.bits.insert(index)
+    }
+
+    pub fn contains(&self, pos: (usize, usize), dir: Dir4) -> bool {
+        self.index(pos, dir).is_some_and(|i| self.bits.contains(i))
+    }
+
+    /// Like [`DirBitGrid::insert`], for the point `(x, y)` at `(row, col) = (y, x)`.
+    pub fn insert_point(&mut self, p: Point, dir: Dir4) -> bool {
+        let pos = p.to_pos().expect("point outside of the grid");
+        self.insert(pos, dir)
+    }
+
+    pub fn contains_point(&self, p: Point, dir: Dir4) -> bool {
+        p.to_pos().is_some_and(|pos| self.contains(pos, dir))
+    }
+
+    /// Number of states in the set.
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.clear();
+    }
+
+    /// Positions that are in the set with any direction.
+    pub fn positions(&self) -> BitGrid {
+        let mut positions = BitGrid::new(self.width, self.height);
+        for i in self.bits.iter() {
+            positions.bits.insert(i / 4);
+        }
+        positions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stores_bits() {
+        let mut bits = BitSet::new(200);
+        assert_eq!(bits.capacity(), 256);
+        assert!(bits.insert(3));
+        assert!(bits.insert(130));
+        assert!(!bits.insert(3));
+        assert!(bits.contains(130) && !bits.contains(4) && !bits.contains(1000));
+        assert_eq!(bits.len(), 2);
+        assert_eq!(bits.iter().collect::<Vec<_>>(), [3, 130]);
+
+        assert!(bits.remove(3));
+        assert!(!bits.remove(3));
+        assert_eq!(bits.len(), 1);
+
+        bits.clear();
+        assert!(bits.is_empty() && !bits.contains(130));
+        assert!(bits.insert(130));
+    }
+
+    #[test]
+    fn stores_positions() {
+        let mut grid = BitGrid::new(3, 2);
+        assert!(grid.insert((1, 2)));
+        assert!(grid.insert_point(Point::new(1, 0)));
+        assert!(!grid.insert((0, 1)));
+        assert!(grid.contains((1, 2)) && grid.contains_point(Point::new(2, 1)));
+        assert!(!grid.contains((2, 0)) && !grid.contains_point(Point::new(-1, 0)));
+        assert_eq!(grid.iter().collect::<Vec<_>>(), [(0, 1), (1, 2)]);
+        assert_eq!(grid.len(), 2);
+
+        assert!(grid.remove((0, 1)));
+        grid.clear();
+        assert!(grid.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "outside")]
+    fn rejects_positions_outside() {
+        BitGrid::new(3, 2).insert((0, 3));
+    }
+
+    #[test]
+    fn stores_directions() {
+        let mut states = DirBitGrid::new(4, 4);
+        assert!(states.insert((1, 1), Dir4::N));
+        assert!(states.insert((1, 1), Dir4::E));
+        assert!(!states.insert_point(Point::new(1, 1), Dir4::N));
+        assert!(states.contains((1, 1), Dir4::E) && !states.contains((1, 1), Dir4::S));
+        assert!(!states.contains_point(Point::new(4, 0), Dir4::N));
+        assert_eq!(states.len(), 2);
+        assert_eq!(states.positions().iter().collect::<Vec<_>>(), [(1, 1)]);
+
+        states.clear();
+        assert!(states.is_empty());
+    }
+}
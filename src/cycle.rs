@@ -0,0 +1,265 @@
+//! Running state machines until they halt or repeat.
+//!
+//! A step function maps a state to the next one, or to `None` if the machine halts. Once a state
+//! repeats, the machine loops forever: the states from [`Cycle::start`] on repeat every
+//! [`Cycle::length`] steps. That makes the state after any number of steps cheap to compute,
+//! see [`state_at`].
+//!
+//! [`run`] remembers every state in a hash map, [`run_brent`] only keeps two states around and
+//! steps up to about three times as often instead.
+use std::{collections::HashMap, hash::Hash};
+
+/// States from step `start` on repeat every `length` steps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The first step with the same state as step `n`.
+    pub fn index(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Run<S> {
+    /// The step function returned `None` for `state`, the state after `steps` steps.
+    Halted { state: S, steps: usize },
+    /// `state` is the state at `cycle.start`, the first one that repeats.
+    Looped { state: S, cycle: Cycle },
+}
+
+impl<S> Run<S> {
+    pub fn is_loop(&self) -> bool {
+        matches!(self, Run::Looped { .. })
+    }
+}
+
+/// Step from `initial` until the machine halts or a state repeats.
+pub fn run<S: Clone + Eq + Hash>(initial: S, step: impl FnMut(&S) -> Option<S>) -> Run<S> {
+    run_by_key(initial, step, S::clone)
+}
+
+/// Like [`run`], but compares states by `key`, e.g. to leave out data that is only collected
+/// along the way and does not influence the next step.
+pub fn run_by_key<S, K: Eq + Hash>(
+    initial: S,
+    mut step: impl FnMut(&S) -> Option<S>,
+    mut key: impl FnMut(&S) -> K,
+) -> Run<S> {
+    let mut seen: HashMap<K, usize> = HashMap::new();
+    let mut state = initial;
+
+    for steps in 0.. {
+        if let Some(&start) = seen.get(&key(&state)) {
+            let length = steps - start;
+            return Run::Looped {
+                state,
+                cycle: Cycle { start, length },
+            };
+        }
+        seen.insert(key(&state), steps);
+
+        match step(&state) {
+            Some(next) => state = next,
+            None => return Run::Halted { state, steps },
+        }
+    }
+
+    unreachable!()
+}
+
+/// Whether the machine loops, with `visit` recording the states seen so far, e.g. in a bitset
+/// that is cheaper than the hash map of [`run`]. `visit` returns whether a state is new.
+pub fn loops_with<S>(
+    initial: S,
+    mut step: impl FnMut(&S) -> Option<S>,
+    mut visit: impl FnMut(&S) -> bool,
+) -> bool {
+    let mut state = initial;
+    loop {
+        if !visit(&state) {
+            return true;
+        }
+        match step(&state) {
+            Some(next) => state = next,
+            None => return false,
+        }
+    }
+}
+
+/// Like [`run`], using Brent's algorithm, which needs no memory but steps from the initial state
+/// again to find the start of the cycle. `step` must therefore always return the same next state.
+pub fn run_brent<S: Clone + Eq>(initial: S, mut step: impl FnMut(&S) -> Option<S>) -> Run<S> {
+    let mut steps = 0;
+    let mut advance = |state: &S, steps: &mut usize| {
+        let next = step(state);
+        *steps += 1;
+        next
+    };
+
+    // find the length: the hare runs ahead of the tortoise, which teleports to the hare whenever
+    // the distance reaches the next power of two.
+    let (mut power, mut length) = (1, 1);
+    let mut tortoise = initial.clone();
+    let Some(mut hare) = advance(&initial, &mut steps) else {
+        return Run::Halted {
+            state: initial,
+            steps: 0,
+        };
+    };
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = match advance(&hare, &mut steps) {
+            Some(next) => next,
+            None => {
+                return Run::Halted {
+                    state: hare,
+                    steps: steps - 1,
+                }
+            }
+        };
+        length += 1;
+    }
+
+    // find the start: with the hare `length` steps ahead, both meet at the start of the cycle.
+    let mut next = |state: &S| advance(state, &mut 0).expect("step function changed its mind");
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..length {
+        hare = next(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = next(&tortoise);
+        hare = next(&hare);
+        start += 1;
+    }
+
+    Run::Looped {
+        state: tortoise,
+        cycle: Cycle { start, length },
+    }
+}
+
+/// The state after `n` steps of a machine that never halts, without running all of them.
+pub fn state_at<S: Clone + Eq + Hash>(initial: S, mut step: impl FnMut(&S) -> S, n: usize) -> S {
+    let mut history: Vec<S> = vec![];
+    let mut seen: HashMap<S, usize> = HashMap::new();
+    let mut state = initial;
+
+    for steps in 0..n {
+        if let Some(&start) = seen.get(&state) {
+            let cycle = Cycle {
+                start,
+                length: steps - start,
+            };
+            return history.swap_remove(cycle.index(n));
+        }
+        seen.insert(state.clone(), steps);
+        let next = step(&state);
+        history.push(state);
+        state = next;
+    }
+
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Enters a cycle of length 6 after 3 steps: 0 1 2 [3 4 5 6 7 8] 3 ...
+    fn rho(&n: &u32) -> u32 {
+        if n == 8 {
+            3
+        } else {
+            n + 1
+        }
+    }
+
+    #[test]
+    fn finds_cycles() {
+        let expected = Run::Looped {
+            state: 3,
+            cycle: Cycle {
+                start: 3,
+                length: 6,
+            },
+        };
+        assert_eq!(run(0, |n| Some(rho(n))), expected);
+        assert_eq!(run_brent(0, |n| Some(rho(n))), expected);
+        // the step counter is left out of the key, otherwise no state would ever repeat.
+        let by_key = run_by_key((0, 0), |&(n, i)| Some((rho(&n), i + 1)), |s| s.0);
+        assert!(matches!(by_key, Run::Looped { state: (3, 9), .. }));
+
+        let fixed_point = Cycle {
+            start: 0,
+            length: 1,
+        };
+        assert_eq!(
+            run(7, |&n| Some(n)),
+            Run::Looped {
+                state: 7,
+                cycle: fixed_point
+            }
+        );
+        assert_eq!(
+            run_brent(7, |&n| Some(n)),
+            Run::Looped {
+                state: 7,
+                cycle: fixed_point
+            }
+        );
+    }
+
+    #[test]
+    fn detects_halting() {
+        let step = |&n: &u32| (n < 5).then_some(n + 1);
+        assert_eq!(run(0, step), Run::Halted { state: 5, steps: 5 });
+        assert_eq!(run_brent(0, step), Run::Halted { state: 5, steps: 5 });
+        assert_eq!(run_brent(5, step), Run::Halted { state: 5, steps: 0 });
+
+        let visitor = || {
+            let mut seen = [false; 10];
+            move |&n: &u32| !std::mem::replace(&mut seen[n as usize], true)
+        };
+        assert!(!loops_with(0, step, visitor()));
+        assert!(loops_with(0, |n| Some(rho(n)), visitor()));
+    }
+
+    #[test]
+    fn agrees_with_brent() {
+        for seed in 0..50u64 {
+            let step = |&x: &u64| Some((x * x + seed) % 1009);
+            assert_eq!(run(seed, step), run_brent(seed, step));
+        }
+    }
+
+    #[test]
+    fn extrapolates_states() {
+        let cycle = Cycle {
+            start: 3,
+            length: 6,
+        };
+        assert_eq!(cycle.index(2), 2);
+        assert_eq!(cycle.index(9), 3);
+        assert_eq!(cycle.index(14), 8);
+
+        for n in 0..40 {
+            let naive = (0..n).fold(0, |state, _| rho(&state));
+            assert_eq!(state_at(0, rho, n), naive);
+        }
+        assert_eq!(state_at(0, rho, 1_000_000_000_000), 4);
+    }
+}
@@ -0,0 +1,495 @@
+//! Searching for operators that turn a list of numbers into a target value, like the calibration
+//! equations of day 7: `3267: 81 40 27` is solved by `81 * 40 + 27`.
+//!
+//! A [`Solver`] is configured with its operators and the [`Order`] it evaluates them in. It finds
+//! the first solution, all of them or just counts them. Solutions are given as indices into
+//! [`Solver::operators`], one for every gap between the numbers, see [`Solver::render`].
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use crate::math::{self, Int};
+
+/// A binary operator, which may fail, e.g. on overflow.
+pub struct Operator<T> {
+    symbol: String,
+    precedence: u8,
+    apply: Box<dyn Fn(T, T) -> Option<T>>,
+}
+
+impl<T: Int> Operator<T> {
+    /// With [`Order::Precedence`], operators of higher `precedence` are applied first.
+    pub fn new(
+        symbol: impl Into<String>,
+        precedence: u8,
+        apply: impl Fn(T, T) -> Option<T> + 'static,
+    ) -> Self {
+        Operator {
+            symbol: symbol.into(),
+            precedence,
+            apply: Box::new(apply),
+        }
+    }
+
+    pub fn add() -> Self {
+        Operator::new("+", 1, <T as Int>::checked_add)
+    }
+
+    pub fn sub() -> Self {
+        Operator::new("-", 1, <T as Int>::checked_sub)
+    }
+
+    pub fn mul() -> Self {
+        Operator::new("*", 2, <T as Int>::checked_mul)
+    }
+
+    /// Division without a remainder, fails otherwise.
+    pub fn div() -> Self {
+        Operator::new("/", 2, |a: T, b: T| {
+            (a.checked_rem(b)? == T::ZERO).then(|| a.checked_div(b))?
+        })
+    }
+
+    /// Concatenation of the decimal digits, `12 || 3 = 123`. Binds tighter than `*`.
+    pub fn concat() -> Self {
+        Operator::new("||", 3, math::checked_concat)
+    }
+
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn precedence(&self) -> u8 {
+        self.precedence
+    }
+
+    pub fn apply(&self, a: T, b: T) -> Option<T> {
+        (self.apply)(a, b)
+    }
+}
+
+impl<T> fmt::Debug for Operator<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Operator")
+            .field("symbol", &self.symbol)
+            .field("precedence", &self.precedence)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Order {
+    /// Strictly left to right, ignoring precedence, as in day 7.
+    #[default]
+    LeftToRight,
+    /// Higher precedence first, operators of the same precedence left to right.
+    Precedence,
+}
+
+/// An expression evaluated up to its last number: `pending` operands with the operator after
+/// them, waiting for the operators to their right that bind tighter, then `current`.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct Partial<T> {
+    pending: Vec<(T, usize)>,
+    current: T,
+}
+
+impl<T> Partial<T> {
+    /// Just a number, with nothing pending.
+    fn number(current: T) -> Self {
+        Partial {
+            pending: vec![],
+            current,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Solver<T> {
+    operators: Vec<Operator<T>>,
+    order: Order,
+    non_decreasing: bool,
+}
+
+impl<T: Int> Solver<T> {
+    /// Try `operators` in the given order, evaluating left to right.
+    pub fn new(operators: impl IntoIterator<Item = Operator<T>>) -> Self {
+        Solver {
+            operators: operators.into_iter().collect(),
+            order: Order::LeftToRight,
+            non_decreasing: false,
+        }
+    }
+
+    pub fn order(mut self, order: Order) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Promise that no operator returns less than either operand, as `+`, `*` and `||` do for
+    /// positive numbers. The search then gives up on expressions once they exceed the target.
+    pub fn non_decreasing(mut self) -> Self {
+        self.non_decreasing = true;
+        self
+    }
+
+    pub fn operators(&self) -> &[Operator<T>] {
+        &self.operators
+    }
+
+    /// Value of `nums` joined by `ops`, `None` if an operator fails.
+    /// Panics unless there is one operator fewer than numbers.
+    pub fn evaluate(&self, nums: &[T], ops: &[usize]) -> Option<T> {
+        assert_eq!(
+            ops.len() + 1,
+            nums.len(),
+            "need an operator between numbers"
+        );
+        let max = self.max_precedence();
+        let mut partial = Partial::number(nums[0]);
+        for (&op, &next) in ops.iter().zip(&nums[1..]) {
+            partial = self.push(&partial, op, next, max)?;
+        }
+        self.finish(partial)
+    }
+
+    /// The expression as text, e.g. `81 * 40 + 27`.
+    pub fn render(&self, nums: &[T], ops: &[usize]) -> String {
+        let mut s = nums[0].to_string();
+        for (&op, next) in ops.iter().zip(&nums[1..]) {
+            s.push_str(&format!(" {} {next}", self.operators[op].symbol));
+        }
+        s
+    }
+
+    /// The first solution, trying operators in the order they were given.
+    pub fn first(&self, nums: &[T], target: T) -> Option<Vec<usize>> {
+        self.solutions(nums, target, 1).pop()
+    }
+
+    /// All solutions, ordered like [`Solver::first`] would find them.
+    pub fn all(&self, nums: &[T], target: T) -> Vec<Vec<usize>> {
+        self.solutions(nums, target, usize::MAX)
+    }
+
+    /// Number of solutions, without listing them.
+    pub fn count(&self, nums: &[T], target: T) -> usize {
+        let Some(&first) = nums.first() else {
+            return 0;
+        };
+        let partial = Partial::number(first);
+        let mut memo = HashMap::new();
+        self.count_from(1, partial, nums, target, &mut memo)
+    }
+
+    fn solutions(&self, nums: &[T], target: T, limit: usize) -> Vec<Vec<usize>> {
+        let mut out = vec![];
+        if let Some(&first) = nums.first() {
+            let mut search = Search {
+                nums,
+                target,
+                limit,
+                dead: HashSet::new(),
+                ops: vec![],
+                out: &mut out,
+            };
+            match self.order {
+                Order::LeftToRight => self.collect_left(1, first, &mut search),
+                Order::Precedence => self.collect(1, Partial::number(first), &mut search),
+            };
+        }
+        out
+    }
+
+    /// [`Solver::collect`] for [`Order::LeftToRight`], where nothing is ever pending: every
+    /// operator is applied as soon as its right operand is known.
+    fn collect_left(&self, i: usize, current: T, search: &mut Search<'_, T>) -> bool {
+        if i == search.nums.len() {
+            let solved = current == search.target;
+            if solved {
+                search.out.push(search.ops.clone());
+            }
+            return solved;
+        }
+        // pruning by value is cheap, remembering dead ends only pays off without it.
+        let key = (!self.non_decreasing).then(|| (i, Partial::number(current)));
+        if self.non_decreasing && current > search.target
+            || key.as_ref().is_some_and(|key| search.dead.contains(key))
+        {
+            return false;
+        }
+
+        let mut found = false;
+        for (op, operator) in self.operators.iter().enumerate() {
+            if search.out.len() >= search.limit {
+                break;
+            }
+            if let Some(next) = operator.apply(current, search.nums[i]) {
+                search.ops.push(op);
+                found |= self.collect_left(i + 1, next, search);
+                search.ops.pop();
+            }
+        }
+
+        if let Some(key) = key.filter(|_| !found) {
+            search.dead.insert(key);
+        }
+        found
+    }
+
+    /// Extend the search from the numbers before `i`, returns whether it found a solution.
+    fn collect(&self, i: usize, partial: Partial<T>, search: &mut Search<'_, T>) -> bool {
+        if i == search.nums.len() {
+            let solved = self.finish(partial) == Some(search.target);
+            if solved {
+                search.out.push(search.ops.clone());
+            }
+            return solved;
+        }
+        if self.exceeds(&partial, search.target) || search.dead.contains(&(i, partial.clone())) {
+            return false;
+        }
+
+        let max = self.max_precedence();
+        let mut found = false;
+        for op in 0..self.operators.len() {
+            if search.out.len() >= search.limit {
+                break;
+            }
+            if let Some(next) = self.push(&partial, op, search.nums[i], max) {
+                search.ops.push(op);
+                found |= self.collect(i + 1, next, search);
+                search.ops.pop();
+            }
+        }
+
+        if !found {
+            search.dead.insert((i, partial));
+        }
+        found
+    }
+
+    fn count_from(
+        &self,
+        i: usize,
+        partial: Partial<T>,
+        nums: &[T],
+        target: T,
+        memo: &mut HashMap<(usize, Partial<T>), usize>,
+    ) -> usize {
+        if i == nums.len() {
+            return usize::from(self.finish(partial) == Some(target));
+        }
+        if self.exceeds(&partial, target) {
+            return 0;
+        }
+        if let Some(&count) = memo.get(&(i, partial.clone())) {
+            return count;
+        }
+
+        let max = self.max_precedence();
+        let count = (0..self.operators.len())
+            .filter_map(|op| self.push(&partial, op, nums[i], max))
+            .map(|next| self.count_from(i + 1, next, nums, target, memo))
+            .sum();
+        memo.insert((i, partial), count);
+        count
+    }
+
+    fn precedence(&self, op: usize) -> u8 {
+        match self.order {
+            Order::LeftToRight => 0,
+            Order::Precedence => self.operators[op].precedence,
+        }
+    }
+
+    fn max_precedence(&self) -> u8 {
+        (0..self.operators.len())
+            .map(|op| self.precedence(op))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Append `op` and `next`, applying the operators to the left that bind at least as tight.
+    fn push(&self, partial: &Partial<T>, op: usize, next: T, max: u8) -> Option<Partial<T>> {
+        let precedence = self.precedence(op);
+        let mut pending = partial.pending.clone();
+        let mut current = partial.current;
+        while let Some(&(left, top)) = pending.last() {
+            if self.precedence(top) < precedence {
+                break;
+            }
+            current = self.operators[top].apply(left, current)?;
+            pending.pop();
+        }
+
+        // nothing binds tighter than the strongest operators, so they can be applied right away.
+        if precedence == max {
+            current = self.operators[op].apply(current, next)?;
+        } else {
+            pending.push((current, op));
+            current = next;
+        }
+        Some(Partial { pending, current })
+    }
+
+    fn finish(&self, partial: Partial<T>) -> Option<T> {
+        let Partial { pending, current } = partial;
+        pending
+            .into_iter()
+            .rev()
+            .try_fold(current, |right, (left, op)| {
+                self.operators[op].apply(left, right)
+            })
+    }
+
+    /// Whether, with non-decreasing operators, the expression can no longer reach `target`.
+    fn exceeds(&self, partial: &Partial<T>, target: T) -> bool {
+        self.non_decreasing
+            && (partial.current > target || partial.pending.iter().any(|&(v, _)| v > target))
+    }
+}
+
+/// State of a search for solutions.
+struct Search<'a, T> {
+    nums: &'a [T],
+    target: T,
+    limit: usize,
+    /// Partial expressions that were found not to lead to a solution.
+    dead: HashSet<(usize, Partial<T>)>,
+    ops: Vec<usize>,
+    out: &'a mut Vec<Vec<usize>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::property;
+
+    fn day_7() -> Solver<u64> {
+        Solver::new([Operator::add(), Operator::mul(), Operator::concat()]).non_decreasing()
+    }
+
+    /// Reference: evaluate every combination of operators.
+    fn brute_force(solver: &Solver<i64>, nums: &[i64], target: i64) -> Vec<Vec<usize>> {
+        let n = solver.operators().len();
+        let gaps = nums.len() - 1;
+        (0..n.pow(gaps as u32))
+            .map(|mut combination| {
+                let mut ops: Vec<usize> = (0..gaps)
+                    .map(|_| {
+                        let op = combination % n;
+                        combination /= n;
+                        op
+                    })
+                    .collect();
+                ops.reverse();
+                ops
+            })
+            .filter(|ops| solver.evaluate(nums, ops) == Some(target))
+            .collect()
+    }
+
+    #[test]
+    fn solves_left_to_right() {
+        let solver = day_7();
+        let ops = solver.first(&[81, 40, 27], 3267).unwrap();
+        assert_eq!(solver.render(&[81, 40, 27], &ops), "81 + 40 * 27");
+        let all: Vec<String> = solver
+            .all(&[81, 40, 27], 3267)
+            .iter()
+            .map(|ops| solver.render(&[81, 40, 27], ops))
+            .collect();
+        assert_eq!(all, ["81 + 40 * 27", "81 * 40 + 27"]);
+        assert_eq!(solver.count(&[81, 40, 27], 3267), 2);
+
+        assert_eq!(solver.first(&[6, 8, 6, 15], 7290), Some(vec![1, 2, 1]));
+        assert_eq!(solver.first(&[2, 2, 2], 7), None);
+        assert_eq!(solver.first(&[5], 5), Some(vec![]));
+        assert_eq!(solver.count(&[], 0), 0);
+    }
+
+    #[test]
+    fn respects_precedence() {
+        let solver = Solver::new([Operator::add(), Operator::mul()]);
+        assert_eq!(solver.evaluate(&[2, 3, 4], &[0, 1]), Some(20));
+        let solver = solver.order(Order::Precedence);
+        assert_eq!(solver.evaluate(&[2, 3, 4], &[0, 1]), Some(14));
+        assert_eq!(solver.evaluate(&[2, 3, 4, 5], &[1, 0, 1]), Some(26));
+
+        let solver = Solver::new([Operator::sub(), Operator::div(), Operator::concat()])
+            .order(Order::Precedence);
+        // 10 - 1 || 2 / 3 = 10 - 4
+        assert_eq!(solver.evaluate(&[10, 1, 2, 3], &[0, 2, 1]), Some(6));
+        // subtraction is left associative
+        assert_eq!(solver.evaluate(&[10, 3, 2], &[0, 0]), Some(5));
+        assert_eq!(solver.evaluate(&[7, 2], &[1]), None);
+        assert_eq!(solver.evaluate(&[7, 0], &[1]), None);
+        // the remainder of `i64::MIN / -1` overflows as well.
+        assert_eq!(Operator::div().apply(i64::MIN, -1), None);
+    }
+
+    #[test]
+    fn supports_custom_operators() {
+        let solver = Solver::new([
+            Operator::new("max", 1, |a: i64, b| Some(a.max(b))),
+            Operator::new("^", 2, |a: i64, b| a.checked_pow(b.try_into().ok()?)),
+        ])
+        .order(Order::Precedence);
+        let ops = solver.first(&[2, 3, 9], 9).unwrap();
+        assert_eq!(solver.render(&[2, 3, 9], &ops), "2 max 3 max 9");
+        assert_eq!(solver.count(&[2, 3, 2], 9), 1);
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let solver = |order| {
+            Solver::new([
+                Operator::add(),
+                Operator::sub(),
+                Operator::mul(),
+                Operator::div(),
+                Operator::concat(),
+            ])
+            .order(order)
+        };
+
+        property::differential(
+            |rng| {
+                let order = *rng.choose(&[Order::LeftToRight, Order::Precedence]);
+                (
+                    order,
+                    rng.vec(1..=5, |rng| rng.range(1..=9)),
+                    rng.range(-20..=100),
+                )
+            },
+            |(order, nums, target)| {
+                let mut out: Vec<_> = property::shrink_vec(nums, |&n| property::shrink_int(n, 1))
+                    .into_iter()
+                    .filter(|nums| !nums.is_empty())
+                    .map(|nums| (*order, nums, *target))
+                    .collect();
+                out.extend(
+                    property::shrink_int(*target, 0)
+                        .into_iter()
+                        .map(|target| (*order, nums.clone(), target)),
+                );
+                out
+            },
+            |(order, nums, target)| {
+                let solver = solver(*order);
+                (
+                    solver.all(nums, *target),
+                    solver.count(nums, *target),
+                    solver.first(nums, *target),
+                )
+            },
+            |(order, nums, target)| {
+                let expected = brute_force(&solver(*order), nums, *target);
+                let (count, first) = (expected.len(), expected.first().cloned());
+                (expected, count, first)
+            },
+        );
+    }
+}
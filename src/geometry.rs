@@ -0,0 +1,375 @@
+//! Points and compass directions on a 2D grid.
+//!
+//! `y` grows downwards like row indices, so [`Dir4::N`] is `(0, -1)` and turning right rotates
+//! clockwise as seen on screen. Grid positions are `(row, col)` pairs, i.e. `(y, x)`.
+use std::{
+    fmt::{self, Display},
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+/// Offsets between points use the same type.
+pub type Vec2 = Point;
+
+impl Point {
+    pub const ZERO: Point = Point { x: 0, y: 0 };
+
+    pub const fn new(x: i64, y: i64) -> Self {
+        Point { x, y }
+    }
+
+    /// The point of the grid position `(row, col)`.
+    pub fn from_pos((row, col): (usize, usize)) -> Self {
+        Point::new(col as i64, row as i64)
+    }
+
+    /// The grid position `(row, col)` of this point, if both coordinates are non-negative.
+    pub fn to_pos(self) -> Option<(usize, usize)> {
+        Some((usize::try_from(self.y).ok()?, usize::try_from(self.x).ok()?))
+    }
+
+    pub fn manhattan(self, other: Point) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// Rotate by 90° clockwise around the origin.
+    pub fn rotate_cw(self) -> Self {
+        Point::new(-self.y, self.x)
+    }
+
+    /// Rotate by 90° counter-clockwise around the origin.
+    pub fn rotate_ccw(self) -> Self {
+        Point::new(self.y, -self.x)
+    }
+
+    /// Orthogonal neighbours, clockwise starting north.
+    pub fn neighbours4(self) -> impl Iterator<Item = Point> {
+        Dir4::iter().map(move |dir| self + dir.delta())
+    }
+
+    /// Orthogonal and diagonal neighbours, clockwise starting north.
+    pub fn neighbours8(self) -> impl Iterator<Item = Point> {
+        Dir8::iter().map(move |dir| self + dir.delta())
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Point) -> Point {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, rhs: Point) -> Point {
+        Point::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, rhs: Point) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, rhs: Point) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+/// Scale a vector.
+impl Mul<i64> for Point {
+    type Output = Point;
+
+    fn mul(self, rhs: i64) -> Point {
+        Point::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl From<(i64, i64)> for Point {
+    fn from((x, y): (i64, i64)) -> Self {
+        Point::new(x, y)
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/// The 4 orthogonal directions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Dir4 {
+    N,
+    E,
+    S,
+    W,
+}
+
+impl Dir4 {
+    /// Clockwise, starting north.
+    pub const ALL: [Dir4; 4] = [Dir4::N, Dir4::E, Dir4::S, Dir4::W];
+
+    pub fn iter() -> impl Iterator<Item = Dir4> {
+        Dir4::ALL.into_iter()
+    }
+
+    /// Position in [`Dir4::ALL`], e.g. to index per-direction tables.
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Turn 90° clockwise.
+    pub fn right(self) -> Self {
+        Dir4::ALL[(self.index() + 1) % 4]
+    }
+
+    /// Turn 90° counter-clockwise.
+    pub fn left(self) -> Self {
+        Dir4::ALL[(self.index() + 3) % 4]
+    }
+
+    pub fn opposite(self) -> Self {
+        Dir4::ALL[(self.index() + 2) % 4]
+    }
+
+    /// Unit step in this direction.
+    pub fn delta(self) -> Vec2 {
+        Dir8::from(self).delta()
+    }
+
+    /// Unit step as a `(row, col)` offset.
+    pub fn offset(self) -> (isize, isize) {
+        Dir8::from(self).offset()
+    }
+
+    /// The arrow pointing in this direction, one of `^>v<`.
+    pub fn symbol(self) -> char {
+        ['^', '>', 'v', '<'][self.index()]
+    }
+
+    /// Parse an arrow (`^>v<`) or a compass letter (`NESW`).
+    pub fn from_char(ch: char) -> Option<Self> {
+        match ch {
+            '^' | 'N' => Some(Dir4::N),
+            '>' | 'E' => Some(Dir4::E),
+            'v' | 'S' => Some(Dir4::S),
+            '<' | 'W' => Some(Dir4::W),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<char> for Dir4 {
+    type Error = String;
+
+    fn try_from(ch: char) -> Result<Self, Self::Error> {
+        Dir4::from_char(ch).ok_or_else(|| format!("{ch:?} is not a direction"))
+    }
+}
+
+impl FromStr for Dir4 {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(ch), None) => Dir4::try_from(ch),
+            _ => Err(format!("{s:?} is not a direction")),
+        }
+    }
+}
+
+/// The 4 orthogonal and 4 diagonal directions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Dir8 {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Dir8 {
+    /// Clockwise, starting north.
+    pub const ALL: [Dir8; 8] = [
+        Dir8::N,
+        Dir8::NE,
+        Dir8::E,
+        Dir8::SE,
+        Dir8::S,
+        Dir8::SW,
+        Dir8::W,
+        Dir8::NW,
+    ];
+
+    const NAMES: [&'static str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+
+    pub fn iter() -> impl Iterator<Item = Dir8> {
+        Dir8::ALL.into_iter()
+    }
+
+    /// Position in [`Dir8::ALL`], e.g. to index per-direction tables.
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Turn 45° clockwise.
+    pub fn right(self) -> Self {
+        Dir8::ALL[(self.index() + 1) % 8]
+    }
+
+    /// Turn 45° counter-clockwise.
+    pub fn left(self) -> Self {
+        Dir8::ALL[(self.index() + 7) % 8]
+    }
+
+    pub fn opposite(self) -> Self {
+        Dir8::ALL[(self.index() + 4) % 8]
+    }
+
+    /// Unit step in this direction, diagonals step along both axes.
+    pub fn delta(self) -> Vec2 {
+        const DELTAS: [(i64, i64); 8] = [
+            (0, -1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+            (0, 1),
+            (-1, 1),
+            (-1, 0),
+            (-1, -1),
+        ];
+        DELTAS[self.index()].into()
+    }
+
+    /// Unit step as a `(row, col)` offset.
+    pub fn offset(self) -> (isize, isize) {
+        let delta = self.delta();
+        (delta.y as isize, delta.x as isize)
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self.index() % 2 == 1
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(dir: Dir4) -> Self {
+        Dir8::ALL[dir.index() * 2]
+    }
+}
+
+/// Parses compass names (`N`, `NE`, ...) as well as arrows (`^>v<`).
+impl FromStr for Dir8 {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(i) = Dir8::NAMES.iter().position(|name| *name == s) {
+            return Ok(Dir8::ALL[i]);
+        }
+        s.parse::<Dir4>()
+            .map(Dir8::from)
+            .map_err(|_| format!("{s:?} is not a direction"))
+    }
+}
+
+impl Display for Dir8 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(Dir8::NAMES[self.index()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn does_point_arithmetic() {
+        let (a, b) = (Point::new(1, 2), Point::new(4, -2));
+        assert_eq!(a + b, Point::new(5, 0));
+        assert_eq!(a - b, Point::new(-3, 4));
+        assert_eq!(-a * 3, Point::new(-3, -6));
+        assert_eq!(a.manhattan(b), 7);
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(c, b);
+    }
+
+    #[test]
+    fn rotates_points() {
+        let north = Dir4::N.delta();
+        assert_eq!(north.rotate_cw(), Dir4::E.delta());
+        assert_eq!(north.rotate_ccw(), Dir4::W.delta());
+        assert_eq!(Point::new(2, 1).rotate_cw().rotate_ccw(), Point::new(2, 1));
+    }
+
+    #[test]
+    fn converts_grid_positions() {
+        assert_eq!(Point::from_pos((2, 5)), Point::new(5, 2));
+        assert_eq!(Point::new(5, 2).to_pos(), Some((2, 5)));
+        assert_eq!(Point::new(-1, 2).to_pos(), None);
+        assert_eq!(Dir4::N.offset(), (-1, 0));
+        assert_eq!(Dir8::SW.offset(), (1, -1));
+    }
+
+    #[test]
+    fn turns_directions() {
+        assert_eq!(Dir4::N.right(), Dir4::E);
+        assert_eq!(Dir4::N.left(), Dir4::W);
+        assert_eq!(Dir4::E.opposite(), Dir4::W);
+        assert!(Dir4::iter().all(|dir| dir.right().right() == dir.opposite()));
+        assert_eq!(Dir8::NW.right(), Dir8::N);
+        assert_eq!(Dir8::N.left(), Dir8::NW);
+        assert_eq!(Dir8::NE.opposite(), Dir8::SW);
+        assert!(Dir8::iter().all(|dir| dir.delta() + dir.opposite().delta() == Point::ZERO));
+        assert_eq!(Dir8::iter().filter(|dir| dir.is_diagonal()).count(), 4);
+    }
+
+    #[test]
+    fn iterates_neighbours() {
+        let neighbours: Vec<Point> = Point::ZERO.neighbours4().collect();
+        assert_eq!(
+            neighbours,
+            [(0, -1), (1, 0), (0, 1), (-1, 0)].map(Point::from)
+        );
+        assert_eq!(Point::ZERO.neighbours8().count(), 8);
+    }
+
+    #[test]
+    fn parses_directions() {
+        let arrows: Vec<Dir4> = "^>v<".chars().map(|ch| ch.try_into().unwrap()).collect();
+        assert_eq!(arrows, Dir4::ALL);
+        assert_eq!("W".parse::<Dir4>(), Ok(Dir4::W));
+        assert!("x".parse::<Dir4>().is_err());
+        assert!("NE".parse::<Dir4>().is_err());
+        assert_eq!("NE".parse::<Dir8>(), Ok(Dir8::NE));
+        assert_eq!("v".parse::<Dir8>(), Ok(Dir8::S));
+        assert_eq!(Dir8::SW.to_string(), "SW");
+        assert_eq!(Dir4::S.symbol(), 'v');
+    }
+}
@@ -0,0 +1,540 @@
+//! Graphs with generic nodes and the usual searches on them.
+//!
+//! [`Graph`] stores nodes in insertion order, so every algorithm on it is deterministic.
+//! [`bfs`], [`dijkstra`] and [`astar`] also work on implicit graphs, e.g. grids, by taking a
+//! closure that returns the successors of a node.
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    fmt::{self, Debug, Display},
+    hash::Hash,
+    ops::Add,
+};
+
+/// Directed graph. Undirected graphs store every edge in both directions.
+#[derive(Clone, Debug)]
+pub struct Graph<N> {
+    nodes: Vec<N>,
+    index: HashMap<N, usize>,
+    edges: Vec<Vec<usize>>,
+}
+
+/// A cycle of nodes, each node has an edge to the next one and the last one to the first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cycle<N>(pub Vec<N>);
+
+/// Prints the cycle as `a -> b -> c -> a`.
+impl<N: Display> Display for Cycle<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for node in &self.0 {
+            write!(f, "{node} -> ")?;
+        }
+        match self.0.first() {
+            Some(first) => write!(f, "{first}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<N: Debug + Display> std::error::Error for Cycle<N> {}
+
+impl<N: Copy + Eq + Hash> Default for Graph<N> {
+    fn default() -> Self {
+        Graph {
+            nodes: vec![],
+            index: HashMap::new(),
+            edges: vec![],
+        }
+    }
+}
+
+impl<N: Copy + Eq + Hash> Graph<N> {
+    pub fn new() -> Self {
+        Graph::default()
+    }
+
+    pub fn directed(edges: impl IntoIterator<Item = (N, N)>) -> Self {
+        let mut graph = Graph::new();
+        for (from, to) in edges {
+            graph.add_edge(from, to);
+        }
+        graph
+    }
+
+    pub fn undirected(edges: impl IntoIterator<Item = (N, N)>) -> Self {
+        let mut graph = Graph::new();
+        for (a, b) in edges {
+            graph.add_edge(a, b);
+            graph.add_edge(b, a);
+        }
+        graph
+    }
+
+    /// Add a node without edges. Adding an existing node does nothing.
+    pub fn add_node(&mut self, node: N) {
+        self.id(node);
+    }
+
+    pub fn add_edge(&mut self, from: N, to: N) {
+        let (from, to) = (self.id(from), self.id(to));
+        self.edges[from].push(to);
+    }
+
+    fn id(&mut self, node: N) -> usize {
+        *self.index.entry(node).or_insert_with(|| {
+            self.nodes.push(node);
+            self.edges.push(vec![]);
+            self.nodes.len() - 1
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn contains(&self, node: &N) -> bool {
+        self.index.contains_key(node)
+    }
+
+    /// All nodes, in insertion order.
+    pub fn nodes(&self) -> impl Iterator<Item = N> + '_ {
+        self.nodes.iter().copied()
+    }
+
+    /// Nodes that `node` has an edge to, in insertion order.
+    pub fn neighbours(&self, node: &N) -> impl Iterator<Item = N> + '_ {
+        let edges = self.index.get(node).map_or(&[][..], |&id| &self.edges[id]);
+        edges.iter().map(|&id| self.nodes[id])
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = (N, N)> + '_ {
+        self.edges.iter().enumerate().flat_map(move |(from, to)| {
+            to.iter().map(move |&to| (self.nodes[from], self.nodes[to]))
+        })
+    }
+
+    /// The graph induced by the nodes matching `keep`.
+    pub fn subgraph(&self, keep: impl Fn(&N) -> bool) -> Self {
+        let mut graph = Graph::new();
+        for node in self.nodes().filter(|n| keep(n)) {
+            graph.add_node(node);
+        }
+        for (from, to) in self.edges().filter(|(from, to)| keep(from) && keep(to)) {
+            graph.add_edge(from, to);
+        }
+        graph
+    }
+
+    /// Topological order, breaking ties by insertion order.
+    pub fn topological_sort(&self) -> Result<Vec<N>, Cycle<N>> {
+        self.topological_sort_by_key(|_| ())
+    }
+
+    /// Topological order (Kahn's algorithm). Of all nodes that are ready, the one with the
+    /// smallest key comes first, ties are broken by insertion order.
+    /// Fails with one of the graph's cycles if there is no such order.
+    pub fn topological_sort_by_key<K: Ord>(
+        &self,
+        key: impl Fn(&N) -> K,
+    ) -> Result<Vec<N>, Cycle<N>> {
+        let mut indegree = vec![0; self.len()];
+        for to in self.edges.iter().flatten() {
+            indegree[*to] += 1;
+        }
+
+        let mut ready: BinaryHeap<Reverse<(K, usize)>> = (0..self.len())
+            .filter(|&id| indegree[id] == 0)
+            .map(|id| Reverse((key(&self.nodes[id]), id)))
+            .collect();
+
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(Reverse((_, id))) = ready.pop() {
+            order.push(self.nodes[id]);
+            for &to in &self.edges[id] {
+                indegree[to] -= 1;
+                if indegree[to] == 0 {
+                    ready.push(Reverse((key(&self.nodes[to]), to)));
+                }
+            }
+        }
+
+        if order.len() == self.len() {
+            Ok(order)
+        } else {
+            Err(self
+                .find_cycle()
+                .expect("nodes left after a topological sort are part of a cycle"))
+        }
+    }
+
+    /// Some cycle of the graph, if it has any. Self-loops are cycles of a single node.
+    pub fn find_cycle(&self) -> Option<Cycle<N>> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum State {
+            New,
+            Active,
+            Done,
+        }
+
+        let mut state = vec![State::New; self.len()];
+
+        for root in 0..self.len() {
+            if state[root] != State::New {
+                continue;
+            }
+            state[root] = State::Active;
+            // the active path, with the index of the next edge to follow from each node.
+            let mut path = vec![(root, 0)];
+
+            while let Some(&(id, edge)) = path.last() {
+                let Some(&to) = self.edges[id].get(edge) else {
+                    state[id] = State::Done;
+                    path.pop();
+                    continue;
+                };
+                path.last_mut().unwrap().1 += 1;
+
+                match state[to] {
+                    State::New => {
+                        state[to] = State::Active;
+                        path.push((to, 0));
+                    }
+                    State::Active => {
+                        let start = path.iter().position(|&(id, _)| id == to).unwrap();
+                        return Some(Cycle(
+                            path[start..]
+                                .iter()
+                                .map(|&(id, _)| self.nodes[id])
+                                .collect(),
+                        ));
+                    }
+                    State::Done => {}
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Number of edges on the shortest path from `start` to every reachable node.
+    pub fn bfs(&self, start: N) -> HashMap<N, usize> {
+        bfs(start, |node| self.neighbours(node).collect::<Vec<_>>())
+    }
+
+    /// Shortest path from `start` to `goal`, including both.
+    pub fn shortest_path(&self, start: N, goal: N) -> Option<Vec<N>> {
+        bfs_path(
+            start,
+            |node| self.neighbours(node).collect::<Vec<_>>(),
+            |node| *node == goal,
+        )
+    }
+
+    /// Components of the graph when ignoring the direction of edges.
+    /// Components and their nodes are in insertion order.
+    pub fn connected_components(&self) -> Vec<Vec<N>> {
+        let mut undirected = self.edges.clone();
+        for (from, to) in self.edges.iter().enumerate() {
+            for &to in to {
+                undirected[to].push(from);
+            }
+        }
+        self.components_of(&undirected, 0..self.len())
+    }
+
+    /// Strongly connected components (Kosaraju's algorithm), nodes within a component are in
+    /// insertion order. Components are in topological order: no edge leads to an earlier one.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<N>> {
+        // order nodes by the time their depth-first search finishes.
+        let mut finished = Vec::with_capacity(self.len());
+        let mut seen = vec![false; self.len()];
+        for root in 0..self.len() {
+            if seen[root] {
+                continue;
+            }
+            seen[root] = true;
+            let mut path = vec![(root, 0)];
+            while let Some(&(id, edge)) = path.last() {
+                match self.edges[id].get(edge) {
+                    Some(&to) => {
+                        path.last_mut().unwrap().1 += 1;
+                        if !seen[to] {
+                            seen[to] = true;
+                            path.push((to, 0));
+                        }
+                    }
+                    None => {
+                        finished.push(id);
+                        path.pop();
+                    }
+                }
+            }
+        }
+
+        let mut reversed = vec![vec![]; self.len()];
+        for (from, to) in self.edges.iter().enumerate() {
+            for &to in to {
+                reversed[to].push(from);
+            }
+        }
+        self.components_of(&reversed, finished.into_iter().rev())
+    }
+
+    /// Nodes reachable via `edges` from each of `roots` that is not part of an earlier component.
+    fn components_of(
+        &self,
+        edges: &[Vec<usize>],
+        roots: impl Iterator<Item = usize>,
+    ) -> Vec<Vec<N>> {
+        let mut component = vec![usize::MAX; self.len()];
+        let mut components: Vec<Vec<usize>> = vec![];
+
+        for root in roots {
+            if component[root] != usize::MAX {
+                continue;
+            }
+            let current = components.len();
+            component[root] = current;
+            let mut members = vec![root];
+            let mut stack = vec![root];
+            while let Some(id) = stack.pop() {
+                for &to in &edges[id] {
+                    if component[to] == usize::MAX {
+                        component[to] = current;
+                        members.push(to);
+                        stack.push(to);
+                    }
+                }
+            }
+            members.sort_unstable();
+            components.push(members);
+        }
+
+        components
+            .into_iter()
+            .map(|ids| ids.into_iter().map(|id| self.nodes[id]).collect())
+            .collect()
+    }
+}
+
+/// Number of steps from `start` to every node reachable via `successors`.
+pub fn bfs<N, I>(start: N, mut successors: impl FnMut(&N) -> I) -> HashMap<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut distances = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([(start, 0)]);
+
+    while let Some((node, distance)) = queue.pop_front() {
+        for next in successors(&node) {
+            if !distances.contains_key(&next) {
+                distances.insert(next.clone(), distance + 1);
+                queue.push_back((next, distance + 1));
+            }
+        }
+    }
+
+    distances
+}
+
+/// Path with the fewest steps from `start` to the first node matching `is_goal`.
+pub fn bfs_path<N, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut parents: HashMap<N, Option<N>> = HashMap::from([(start.clone(), None)]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(node) = queue.pop_front() {
+        if is_goal(&node) {
+            return Some(unwind(&parents, node));
+        }
+        for next in successors(&node) {
+            if !parents.contains_key(&next) {
+                parents.insert(next.clone(), Some(node.clone()));
+                queue.push_back(next);
+            }
+        }
+    }
+
+    None
+}
+
+fn unwind<N: Clone + Eq + Hash>(parents: &HashMap<N, Option<N>>, goal: N) -> Vec<N> {
+    let mut path = vec![goal];
+    while let Some(Some(parent)) = parents.get(path.last().unwrap()) {
+        path.push(parent.clone());
+    }
+    path.reverse();
+    path
+}
+
+/// Cheapest path from `start` to the first node matching `is_goal`, with its cost.
+/// `successors` returns the neighbours of a node with the cost of the edge to them.
+pub fn dijkstra<N, C, I>(
+    start: N,
+    successors: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, successors, |_| C::default(), is_goal)
+}
+
+/// Like [`dijkstra`], guided by `heuristic`, an estimate of the remaining cost to a goal.
+/// The heuristic must never overestimate and must be consistent, i.e. not drop by more than the
+/// cost of an edge, otherwise the result might not be the cheapest path.
+pub fn astar<N, C, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    // nodes are referred to by the order they were discovered in, so they need not be `Ord`.
+    let mut ids: HashMap<N, usize> = HashMap::from([(start.clone(), 0)]);
+    let mut nodes = vec![start];
+    let mut costs = vec![C::default()];
+    let mut parents = vec![usize::MAX];
+    let mut closed = vec![false];
+
+    let mut open = BinaryHeap::from([Reverse((heuristic(&nodes[0]), 0))]);
+
+    while let Some(Reverse((_, id))) = open.pop() {
+        if closed[id] {
+            continue;
+        }
+        closed[id] = true;
+
+        if is_goal(&nodes[id]) {
+            let mut path = vec![id];
+            while parents[*path.last().unwrap()] != usize::MAX {
+                path.push(parents[*path.last().unwrap()]);
+            }
+            let path = path.into_iter().rev().map(|id| nodes[id].clone()).collect();
+            return Some((path, costs[id]));
+        }
+
+        for (next, step) in successors(&nodes[id].clone()) {
+            let cost = costs[id] + step;
+            let next_id = match ids.get(&next) {
+                Some(&next_id) if closed[next_id] || cost >= costs[next_id] => continue,
+                Some(&next_id) => {
+                    costs[next_id] = cost;
+                    parents[next_id] = id;
+                    next_id
+                }
+                None => {
+                    ids.insert(next.clone(), nodes.len());
+                    nodes.push(next);
+                    costs.push(cost);
+                    parents.push(id);
+                    closed.push(false);
+                    nodes.len() - 1
+                }
+            };
+            open.push(Reverse((cost + heuristic(&nodes[next_id]), next_id)));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_graphs() {
+        let graph = Graph::undirected([("a", "b"), ("b", "c")]);
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.neighbours(&"b").collect::<Vec<_>>(), ["a", "c"]);
+        assert_eq!(graph.neighbours(&"x").count(), 0);
+        assert_eq!(graph.edges().count(), 4);
+
+        let sub = graph.subgraph(|n| *n != "c");
+        assert_eq!(sub.nodes().collect::<Vec<_>>(), ["a", "b"]);
+        assert_eq!(sub.edges().collect::<Vec<_>>(), [("a", "b"), ("b", "a")]);
+    }
+
+    #[test]
+    fn sorts_topologically() {
+        let graph = Graph::directed([(3, 1), (2, 1), (1, 0)]);
+        assert_eq!(graph.topological_sort(), Ok(vec![3, 2, 1, 0]));
+        assert_eq!(graph.topological_sort_by_key(|&n| n), Ok(vec![2, 3, 1, 0]));
+
+        let mut graph = Graph::new();
+        graph.add_node(9);
+        graph.add_node(8);
+        assert_eq!(graph.topological_sort(), Ok(vec![9, 8]));
+    }
+
+    #[test]
+    fn reports_cycles() {
+        let graph = Graph::directed([(0, 1), (1, 2), (2, 3), (3, 1), (3, 4)]);
+        let cycle = graph.topological_sort().unwrap_err();
+        assert_eq!(cycle, Cycle(vec![1, 2, 3]));
+        assert_eq!(cycle.to_string(), "1 -> 2 -> 3 -> 1");
+
+        assert_eq!(Graph::directed([(5, 5)]).find_cycle(), Some(Cycle(vec![5])));
+        assert_eq!(Graph::directed([(0, 1), (0, 2), (1, 2)]).find_cycle(), None);
+    }
+
+    #[test]
+    fn searches_breadth_first() {
+        let graph = Graph::undirected([("a", "b"), ("b", "c"), ("a", "d"), ("d", "c")]);
+        let distances = graph.bfs("a");
+        assert_eq!((distances["a"], distances["c"]), (0, 2));
+        assert_eq!(graph.shortest_path("a", "c"), Some(vec!["a", "b", "c"]));
+        assert_eq!(graph.shortest_path("a", "x"), None);
+    }
+
+    #[test]
+    fn finds_cheapest_paths() {
+        // a line of numbers where stepping by 1 costs 3 and stepping by 2 costs 5.
+        let successors = |&n: &i64| [(n + 1, 3), (n + 2, 5)];
+        assert_eq!(
+            dijkstra(0, successors, |&n| n == 4),
+            Some((vec![0, 2, 4], 10))
+        );
+        assert_eq!(
+            astar(0, successors, |&n| (4 - n).max(0) * 2, |&n| n == 5),
+            Some((vec![0, 1, 3, 5], 13))
+        );
+        assert_eq!(
+            dijkstra(0, |_: &i64| [], |&n| n == 1),
+            None::<(Vec<i64>, u32)>
+        );
+    }
+
+    #[test]
+    fn finds_components() {
+        let mut graph = Graph::directed([(1, 2), (2, 1), (2, 3), (4, 5), (5, 6), (6, 4)]);
+        graph.add_node(7);
+        assert_eq!(
+            graph.connected_components(),
+            [vec![1, 2, 3], vec![4, 5, 6], vec![7]]
+        );
+        assert_eq!(
+            graph.strongly_connected_components(),
+            [vec![7], vec![4, 5, 6], vec![1, 2], vec![3]]
+        );
+    }
+}
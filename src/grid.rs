@@ -0,0 +1,428 @@
+//! Rectangular grids with flat, row-major storage.
+//!
+//! Positions are `(row, col)` pairs, like in [`crate::visualize::Frame`]. Lookups take signed
+//! coordinates so neighbours and rays can step off the grid without wrapping around.
+use std::{
+    fmt::{self, Display},
+    ops::{Index, IndexMut},
+};
+
+use crate::geometry::{Dir4, Dir8, Point};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl Grid<char> {
+    /// Parse a rectangular char grid, all lines must have equal length.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        Grid::parse_with(input, |ch| ch)
+    }
+}
+
+impl Grid<u8> {
+    /// Parse a rectangular byte grid, all lines must have equal length.
+    pub fn parse_bytes(input: &str) -> Result<Self, String> {
+        Grid::from_rows(input.lines().map(|line| line.as_bytes().to_vec()).collect())
+    }
+}
+
+impl<T> Grid<T> {
+    /// Parse a rectangular grid, converting every char with `f`.
+    pub fn parse_with(input: &str, mut f: impl FnMut(char) -> T) -> Result<Self, String> {
+        Grid::from_rows(
+            input
+                .lines()
+                .map(|line| line.chars().map(&mut f).collect())
+                .collect(),
+        )
+    }
+
+    /// Build a grid from its rows, which must be non-empty and of equal length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, String> {
+        if rows.is_empty() {
+            return Err("empty input".into());
+        }
+        let width = rows[0].len();
+        if width == 0 {
+            return Err("lines must be non-empty".into());
+        }
+
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+        for (i, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(format!(
+                    "line {i} has length {}, expected {width}",
+                    row.len()
+                ));
+            }
+            cells.extend(row);
+        }
+
+        Ok(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Number of cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, row: isize, col: isize) -> bool {
+        self.index_of(row, col).is_some()
+    }
+
+    fn index_of(&self, row: isize, col: isize) -> Option<usize> {
+        let (row, col) = (usize::try_from(row).ok()?, usize::try_from(col).ok()?);
+        (row < self.height && col < self.width).then_some(row * self.width + col)
+    }
+
+    /// The cell at `(row, col)`, or `None` if it lies outside the grid.
+    pub fn get(&self, row: isize, col: isize) -> Option<&T> {
+        self.index_of(row, col).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, row: isize, col: isize) -> Option<&mut T> {
+        self.index_of(row, col).map(|i| &mut self.cells[i])
+    }
+
+    pub fn contains_point(&self, p: Point) -> bool {
+        self.get_point(p).is_some()
+    }
+
+    /// The cell at `p`, or `None` if it lies outside the grid.
+    pub fn get_point(&self, p: Point) -> Option<&T> {
+        let (row, col) = (isize::try_from(p.y).ok()?, isize::try_from(p.x).ok()?);
+        self.get(row, col)
+    }
+
+    pub fn get_point_mut(&mut self, p: Point) -> Option<&mut T> {
+        let (row, col) = (isize::try_from(p.y).ok()?, isize::try_from(p.x).ok()?);
+        self.get_mut(row, col)
+    }
+
+    /// All cells with their positions, in reading order.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| ((i / width, i % width), cell))
+    }
+
+    /// Positions inside the grid that are offset from `(row, col)` by one of `offsets`.
+    pub fn neighbours<'a>(
+        &'a self,
+        (row, col): (usize, usize),
+        offsets: impl IntoIterator<Item = (isize, isize)> + 'a,
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        offsets.into_iter().filter_map(move |(dr, dc)| {
+            let (r, c) = (row as isize + dr, col as isize + dc);
+            self.contains(r, c).then_some((r as usize, c as usize))
+        })
+    }
+
+    /// Orthogonal neighbours of `pos` inside the grid, clockwise starting north.
+    pub fn neighbours4(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbours(pos, Dir4::iter().map(Dir4::offset))
+    }
+
+    /// Orthogonal and diagonal neighbours of `pos` inside the grid, clockwise starting north.
+    pub fn neighbours8(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbours(pos, Dir8::iter().map(Dir8::offset))
+    }
+
+    /// Positions from `start` (inclusive) in steps of `(dr, dc)` until the ray leaves the grid.
+    pub fn ray(
+        &self,
+        start: (usize, usize),
+        (dr, dc): (isize, isize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..)
+            .map(move |k| (start.0 as isize + k * dr, start.1 as isize + k * dc))
+            .take_while(|&(r, c)| self.contains(r, c))
+            .map(|(r, c)| (r as usize, c as usize))
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        self.cells[col..].iter().step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|col| self.column(col))
+    }
+
+    /// Diagonals running down and to the right, starting with the bottom left corner.
+    pub fn diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let starts = (0..self.height)
+            .rev()
+            .map(|row| (row, 0))
+            .chain((1..self.width).map(|col| (0, col)));
+        starts.map(|start| self.ray(start, (1, 1)).map(|pos| &self[pos]))
+    }
+
+    /// Diagonals running down and to the left, starting with the top left corner.
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let starts = (0..self.width)
+            .map(|col| (0, col))
+            .chain((1..self.height).map(|row| (row, self.width - 1)));
+        starts.map(|start| self.ray(start, (1, -1)).map(|pos| &self[pos]))
+    }
+
+    /// Position of the first cell matching `predicate`, in reading order.
+    pub fn find(&self, predicate: impl Fn(&T) -> bool) -> Option<(usize, usize)> {
+        self.positions(predicate).next()
+    }
+
+    /// Positions of all cells matching `predicate`, in reading order.
+    pub fn positions<'a>(
+        &'a self,
+        predicate: impl Fn(&T) -> bool + 'a,
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        self.iter()
+            .filter(move |(_, cell)| predicate(cell))
+            .map(|(pos, _)| pos)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Build a grid of the given size where cell `(row, col)` is `f((row, col))`.
+    fn from_fn(width: usize, height: usize, f: impl FnMut((usize, usize)) -> T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: (0..width * height)
+                .map(|i| (i / width, i % width))
+                .map(f)
+                .collect(),
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Mirror along the main diagonal, rows become columns.
+    pub fn transpose(&self) -> Self {
+        Grid::from_fn(self.height, self.width, |(r, c)| self[(c, r)].clone())
+    }
+
+    /// Rotate by 90° clockwise.
+    pub fn rotate_cw(&self) -> Self {
+        Grid::from_fn(self.height, self.width, |(r, c)| {
+            self[(self.height - 1 - c, r)].clone()
+        })
+    }
+
+    /// Rotate by 90° counter-clockwise.
+    pub fn rotate_ccw(&self) -> Self {
+        Grid::from_fn(self.height, self.width, |(r, c)| {
+            self[(c, self.width - 1 - r)].clone()
+        })
+    }
+
+    /// Mirror left to right.
+    pub fn flip_horizontal(&self) -> Self {
+        Grid::from_fn(self.width, self.height, |(r, c)| {
+            self[(r, self.width - 1 - c)].clone()
+        })
+    }
+
+    /// Mirror top to bottom.
+    pub fn flip_vertical(&self) -> Self {
+        Grid::from_fn(self.width, self.height, |(r, c)| {
+            self[(self.height - 1 - r, c)].clone()
+        })
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        assert!(
+            row < self.height && col < self.width,
+            "position ({row}, {col}) is outside of the {}x{} grid",
+            self.width,
+            self.height
+        );
+        &self.cells[row * self.width + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        assert!(
+            row < self.height && col < self.width,
+            "position ({row}, {col}) is outside of the {}x{} grid",
+            self.width,
+            self.height
+        );
+        &mut self.cells[row * self.width + col]
+    }
+}
+
+/// Prints one line per row, without separators between cells.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<char> {
+        Grid::parse("abc\ndef").unwrap()
+    }
+
+    fn collect<'a>(lines: impl Iterator<Item = impl Iterator<Item = &'a char>>) -> Vec<String> {
+        lines.map(|line| line.collect()).collect()
+    }
+
+    #[test]
+    fn parses_grids() {
+        let grid = grid();
+        assert_eq!((grid.width(), grid.height(), grid.len()), (3, 2, 6));
+        assert_eq!(grid[(1, 2)], 'f');
+        assert_eq!(grid.to_string(), "abc\ndef");
+        assert_eq!(Grid::parse_bytes("#.\n.#").unwrap()[(1, 1)], b'#');
+        assert_eq!(
+            Grid::parse_with("12\n34", |ch| ch.to_digit(10).unwrap())
+                .unwrap()
+                .row(1),
+            [3, 4]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_grids() {
+        assert_eq!(Grid::parse(""), Err("empty input".into()));
+        assert_eq!(Grid::parse("\n"), Err("lines must be non-empty".into()));
+        assert_eq!(
+            Grid::parse("abc\nde"),
+            Err("line 1 has length 2, expected 3".into())
+        );
+    }
+
+    #[test]
+    fn gets_cells_by_signed_coordinates() {
+        let mut grid = grid();
+        assert_eq!(grid.get(0, 1), Some(&'b'));
+        assert_eq!(grid.get(-1, 0), None);
+        assert_eq!(grid.get(0, 3), None);
+        assert_eq!(grid.get(2, 0), None);
+        *grid.get_mut(1, 0).unwrap() = 'x';
+        assert_eq!(grid.row(1), ['x', 'e', 'f']);
+        assert!(grid.get_mut(0, -1).is_none());
+        assert_eq!(grid.get_point(Point::new(1, 0)), Some(&'b'));
+        assert!(!grid.contains_point(Point::new(0, -1)));
+    }
+
+    #[test]
+    fn iterates_neighbours() {
+        let grid = grid();
+        assert_eq!(
+            grid.neighbours4((0, 0)).collect::<Vec<_>>(),
+            [(0, 1), (1, 0)]
+        );
+        assert_eq!(
+            grid.neighbours8((0, 1)).collect::<Vec<_>>(),
+            [(0, 2), (1, 2), (1, 1), (1, 0), (0, 0)]
+        );
+        assert_eq!(
+            grid.ray((0, 0), (1, 1)).collect::<Vec<_>>(),
+            [(0, 0), (1, 1)]
+        );
+    }
+
+    #[test]
+    fn iterates_lines() {
+        let grid = grid();
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            [['a', 'b', 'c'], ['d', 'e', 'f']]
+        );
+        assert_eq!(collect(grid.columns()), ["ad", "be", "cf"]);
+        assert_eq!(collect(grid.diagonals()), ["d", "ae", "bf", "c"]);
+        assert_eq!(collect(grid.anti_diagonals()), ["a", "bd", "ce", "f"]);
+    }
+
+    #[test]
+    fn finds_cells() {
+        let grid = Grid::parse("#.#\n.##").unwrap();
+        assert_eq!(grid.find(|&ch| ch == '.'), Some((0, 1)));
+        assert_eq!(grid.find(|&ch| ch == 'x'), None);
+        assert_eq!(
+            grid.positions(|&ch| ch == '#').collect::<Vec<_>>(),
+            [(0, 0), (0, 2), (1, 1), (1, 2)]
+        );
+    }
+
+    #[test]
+    fn transforms_grids() {
+        let grid = grid();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotate_cw().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotate_ccw().to_string(), "cf\nbe\nad");
+        assert_eq!(grid.flip_horizontal().to_string(), "cba\nfed");
+        assert_eq!(grid.flip_vertical().to_string(), "def\nabc");
+        assert_eq!(
+            grid.rotate_cw().rotate_cw(),
+            grid.flip_horizontal().flip_vertical()
+        );
+        assert_eq!(
+            grid.map(|ch| ch.to_ascii_uppercase()).to_string(),
+            "ABC\nDEF"
+        );
+        assert_eq!(Grid::new(2, 1, 0).to_string(), "00");
+    }
+}
@@ -0,0 +1,244 @@
+//! Finding `name(args)` instructions in corrupted memory and running them, like day 3.
+//!
+//! A [`Scanner`] looks for instructions like `mul(2,4)` or `do()` anywhere in the input and
+//! ignores everything else, including instructions with the wrong number of arguments or
+//! arguments of the wrong length. An [`InstructionSet`] adds a handler to every instruction and
+//! runs them on a [`Vm`], so different puzzle parts are just different instruction sets:
+//!
+//! ```text
+//! xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))
+//! ```
+use std::ops::RangeInclusive;
+
+/// An instruction `name(a,b,...)` with `arity` unsigned decimal arguments.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Spec {
+    pub name: String,
+    pub arity: usize,
+    /// Allowed number of digits of every argument.
+    pub digits: RangeInclusive<usize>,
+}
+
+impl Spec {
+    pub fn new(name: impl Into<String>, arity: usize, digits: RangeInclusive<usize>) -> Self {
+        Spec {
+            name: name.into(),
+            arity,
+            digits,
+        }
+    }
+
+    /// An instruction without arguments, like `do()`.
+    pub fn nullary(name: impl Into<String>) -> Self {
+        Spec::new(name, 0, 0..=0)
+    }
+
+    /// The arguments if `text` starts with this instruction, and the length of the instruction.
+    fn parse(&self, text: &str) -> Option<(Vec<u64>, usize)> {
+        let mut rest = text.strip_prefix(self.name.as_str())?.strip_prefix('(')?;
+        let mut args = Vec::with_capacity(self.arity);
+        for i in 0..self.arity {
+            if i > 0 {
+                rest = rest.strip_prefix(',')?;
+            }
+            let len = rest.bytes().take_while(u8::is_ascii_digit).count();
+            if !self.digits.contains(&len) {
+                return None;
+            }
+            args.push(rest[..len].parse().ok()?);
+            rest = &rest[len..];
+        }
+        rest = rest.strip_prefix(')')?;
+        Some((args, text.len() - rest.len()))
+    }
+}
+
+/// An instruction found in the input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Instruction {
+    /// Index of the matching [`Spec`] of the scanner.
+    pub spec: usize,
+    pub args: Vec<u64>,
+    /// Byte offset in the input.
+    pub offset: usize,
+}
+
+/// Finds instructions of the given specs in arbitrary text.
+#[derive(Clone, Debug, Default)]
+pub struct Scanner {
+    specs: Vec<Spec>,
+}
+
+impl Scanner {
+    pub fn new(specs: impl IntoIterator<Item = Spec>) -> Self {
+        Scanner {
+            specs: specs.into_iter().collect(),
+        }
+    }
+
+    pub fn specs(&self) -> &[Spec] {
+        &self.specs
+    }
+
+    /// Instructions in the order they appear. Where several specs match, the first one wins,
+    /// scanning continues after the instruction that was found.
+    pub fn scan<'a>(&'a self, input: &'a str) -> impl Iterator<Item = Instruction> + 'a {
+        let mut offset = 0;
+        std::iter::from_fn(move || {
+            while offset < input.len() {
+                let rest = &input[offset..];
+                let found = self
+                    .specs
+                    .iter()
+                    .enumerate()
+                    .find_map(|(spec, s)| s.parse(rest).map(|(args, len)| (spec, args, len)));
+                match found {
+                    Some((spec, args, len)) => {
+                        let instruction = Instruction { spec, args, offset };
+                        offset += len;
+                        return Some(instruction);
+                    }
+                    None => offset += rest.chars().next().map_or(1, char::len_utf8),
+                }
+            }
+            None
+        })
+    }
+}
+
+/// State of a running program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Vm {
+    /// Whether conditional instructions like `mul` take effect.
+    pub enabled: bool,
+    /// The result so far.
+    pub total: u64,
+}
+
+impl Default for Vm {
+    fn default() -> Self {
+        Vm {
+            enabled: true,
+            total: 0,
+        }
+    }
+}
+
+/// Runs an instruction with its arguments.
+pub type Handler = fn(&mut Vm, &[u64]);
+
+/// Adds the product of the arguments to the total, if enabled.
+pub fn mul(vm: &mut Vm, args: &[u64]) {
+    if vm.enabled {
+        vm.total += args.iter().product::<u64>();
+    }
+}
+
+pub fn enable(vm: &mut Vm, _: &[u64]) {
+    vm.enabled = true;
+}
+
+pub fn disable(vm: &mut Vm, _: &[u64]) {
+    vm.enabled = false;
+}
+
+/// Instructions with their handlers.
+#[derive(Clone, Debug, Default)]
+pub struct InstructionSet {
+    scanner: Scanner,
+    handlers: Vec<Handler>,
+}
+
+impl InstructionSet {
+    pub fn new() -> Self {
+        InstructionSet::default()
+    }
+
+    /// Add an instruction. Earlier instructions win if several match at the same position.
+    pub fn with(mut self, spec: Spec, handler: Handler) -> Self {
+        self.scanner.specs.push(spec);
+        self.handlers.push(handler);
+        self
+    }
+
+    pub fn scanner(&self) -> &Scanner {
+        &self.scanner
+    }
+
+    /// Run all instructions of `input` on a fresh [`Vm`].
+    pub fn run(&self, input: &str) -> Vm {
+        let mut vm = Vm::default();
+        self.run_on(&mut vm, input);
+        vm
+    }
+
+    pub fn run_on(&self, vm: &mut Vm, input: &str) {
+        for instruction in self.scanner.scan(input) {
+            self.handlers[instruction.spec](vm, &instruction.args);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str =
+        "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+    fn day_3(conditionals: bool) -> InstructionSet {
+        let set = InstructionSet::new().with(Spec::new("mul", 2, 1..=3), mul);
+        if conditionals {
+            set.with(Spec::nullary("do"), enable)
+                .with(Spec::nullary("don't"), disable)
+        } else {
+            set
+        }
+    }
+
+    #[test]
+    fn scans_instructions() {
+        let set = day_3(true);
+        let found: Vec<(&str, Vec<u64>, usize)> = set
+            .scanner()
+            .scan(EXAMPLE)
+            .map(|i| {
+                (
+                    set.scanner().specs()[i.spec].name.as_str(),
+                    i.args,
+                    i.offset,
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("mul", vec![2, 4], 1),
+                ("don't", vec![], 20),
+                ("mul", vec![5, 5], 28),
+                ("mul", vec![11, 8], 48),
+                ("do", vec![], 59),
+                ("mul", vec![8, 5], 64),
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_arguments() {
+        let scanner = Scanner::new([Spec::new("mul", 2, 1..=3), Spec::new("neg", 1, 1..=9)]);
+        let args = |input| scanner.scan(input).map(|i| i.args).collect::<Vec<_>>();
+        assert!(args("mul(1234,5)mul(1,)mul(1,2,3)mul( 1,2)").is_empty());
+        assert_eq!(args("mumul(12,345)neg(7)neg()"), [vec![12, 345], vec![7]]);
+        assert_eq!(args("é mul(1,1)"), [vec![1, 1]]);
+    }
+
+    #[test]
+    fn runs_programs() {
+        assert_eq!(day_3(false).run(EXAMPLE).total, 161);
+        let vm = day_3(true).run(EXAMPLE);
+        assert_eq!((vm.total, vm.enabled), (48, true));
+
+        let set = day_3(true).with(Spec::nullary("reset"), |vm, _| vm.total = 0);
+        assert_eq!(set.run("mul(2,3)reset()mul(4,5)").total, 20);
+    }
+}
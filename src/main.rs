@@ -1,13 +1,15 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
+use advent_of_code::template::commands::{
+    all, download, leaderboard, read, scaffold, solve, stars, stats, status, time,
+};
 use args::{parse, AppArguments};
 
 #[cfg(feature = "today")]
-use advent_of_code::template::Day;
+use advent_of_code::template::{templates::DEFAULT_TEMPLATE, Day};
 #[cfg(feature = "today")]
 use std::process;
 
 mod args {
-    use advent_of_code::template::Day;
+    use advent_of_code::template::{templates, Day};
     use std::process;
 
     pub enum AppArguments {
@@ -21,12 +23,26 @@ mod args {
             day: Day,
             download: bool,
             overwrite: bool,
+            dry_run: bool,
+            template: String,
+            examples: [Option<String>; 2],
         },
         Solve {
             day: Day,
             release: bool,
             dhat: bool,
             submit: Option<u8>,
+            all_variants: bool,
+            explain: bool,
+            watch: bool,
+            test: bool,
+            visualize: bool,
+            fps: Option<u32>,
+            step: bool,
+            export: Option<String>,
+            format: Option<String>,
+            scale: Option<u32>,
+            every: Option<u32>,
         },
         All {
             release: bool,
@@ -36,6 +52,18 @@ mod args {
             day: Option<Day>,
             store: bool,
         },
+        Stats {
+            store: bool,
+        },
+        Stars,
+        Status {
+            run_tests: bool,
+        },
+        Leaderboard {
+            id: Option<u64>,
+            file: Option<String>,
+            day: Option<Day>,
+        },
         #[cfg(feature = "today")]
         Today,
     }
@@ -67,12 +95,43 @@ mod args {
                 day: args.free_from_str()?,
                 download: args.contains("--download"),
                 overwrite: args.contains("--overwrite"),
+                dry_run: args.contains("--dry-run"),
+                template: args
+                    .opt_value_from_str("--template")?
+                    .unwrap_or_else(|| templates::DEFAULT_TEMPLATE.into()),
+                examples: [
+                    args.opt_value_from_str("--expect-1")?,
+                    args.opt_value_from_str("--expect-2")?,
+                ],
             },
             Some("solve") => AppArguments::Solve {
                 day: args.free_from_str()?,
                 release: args.contains("--release"),
                 submit: args.opt_value_from_str("--submit")?,
                 dhat: args.contains("--dhat"),
+                all_variants: args.contains("--all-variants"),
+                explain: args.contains("--explain"),
+                watch: args.contains("--watch"),
+                test: args.contains("--test"),
+                visualize: args.contains("--visualize"),
+                fps: args.opt_value_from_str("--fps")?,
+                step: args.contains("--step"),
+                export: args.opt_value_from_str("--export")?,
+                format: args.opt_value_from_str("--format")?,
+                scale: args.opt_value_from_str("--scale")?,
+                every: args.opt_value_from_str("--every")?,
+            },
+            Some("stats") => AppArguments::Stats {
+                store: args.contains("--store"),
+            },
+            Some("stars") => AppArguments::Stars,
+            Some("status") => AppArguments::Status {
+                run_tests: !args.contains("--no-tests"),
+            },
+            Some("leaderboard") => AppArguments::Leaderboard {
+                id: args.opt_value_from_str("--id")?,
+                file: args.opt_value_from_str("--file")?,
+                day: args.opt_free_from_str()?,
             },
             #[cfg(feature = "today")]
             Some("today") => AppArguments::Today,
@@ -110,9 +169,12 @@ fn main() {
                 day,
                 download,
                 overwrite,
+                dry_run,
+                template,
+                examples,
             } => {
-                scaffold::handle(day, overwrite);
-                if download {
+                scaffold::handle(day, overwrite, dry_run, &template, examples);
+                if download && !dry_run {
                     download::handle(day);
                 }
             }
@@ -121,12 +183,45 @@ fn main() {
                 release,
                 dhat,
                 submit,
-            } => solve::handle(day, release, dhat, submit),
+                all_variants,
+                explain,
+                watch,
+                test,
+                visualize,
+                fps,
+                step,
+                export,
+                format,
+                scale,
+                every,
+            } => solve::handle(
+                day,
+                solve::RunOptions {
+                    release,
+                    dhat,
+                    all_variants,
+                    explain,
+                    visualize: (visualize || export.is_some()).then_some(solve::VisualizeOptions {
+                        fps,
+                        step,
+                        export,
+                        format,
+                        scale,
+                        every,
+                    }),
+                },
+                submit,
+                watch.then_some(solve::WatchOptions { test }),
+            ),
+            AppArguments::Stats { store } => stats::handle(store),
+            AppArguments::Stars => stars::handle(),
+            AppArguments::Status { run_tests } => status::handle(run_tests),
+            AppArguments::Leaderboard { id, file, day } => leaderboard::handle(id, file, day),
             #[cfg(feature = "today")]
             AppArguments::Today => {
                 match Day::today() {
                     Some(day) => {
-                        scaffold::handle(day, false);
+                        scaffold::handle(day, false, false, DEFAULT_TEMPLATE, [None, None]);
                         download::handle(day);
                         read::handle(day)
                     }
@@ -0,0 +1,339 @@
+//! Number theory and decimal digit helpers, generic over the primitive integer types.
+//!
+//! Functions that can overflow come in two flavours, like the std integer methods: the plain
+//! one panics on overflow, the `checked_` one returns `None` instead.
+//! Modular arithmetic never overflows, products are computed in a wider type.
+use std::{
+    fmt::{Debug, Display},
+    hash::Hash,
+    ops::{Add, Div, Mul, Neg, Rem, Sub},
+};
+
+/// A primitive integer type.
+pub trait Int:
+    'static
+    + Copy
+    + Ord
+    + Hash
+    + Debug
+    + Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const TWO: Self;
+    const TEN: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    /// `None` when dividing by zero or overflowing.
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+    /// `None` when dividing by zero or overflowing, like `checked_div`.
+    fn checked_rem(self, rhs: Self) -> Option<Self>;
+    /// The remainder in `0..m`, also for negative values.
+    fn rem_euclid(self, m: Self) -> Self;
+
+    /// `self * rhs % m` without overflowing, for `self` and `rhs` in `0..m`.
+    fn mul_mod(self, rhs: Self, m: Self) -> Self {
+        // double and add, for types without a wider type.
+        let (mut a, mut b, mut product) = (self, rhs, Self::ZERO);
+        while b > Self::ZERO {
+            if b % Self::TWO == Self::ONE {
+                product = add_mod(product, a, m);
+            }
+            a = add_mod(a, a, m);
+            b = b / Self::TWO;
+        }
+        product
+    }
+}
+
+/// A signed primitive integer type.
+pub trait SignedInt: Int + Neg<Output = Self> {}
+
+macro_rules! impl_int {
+    ($($t:ty $(=> $wide:ty)?),+) => {$(
+        impl Int for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            const TWO: Self = 2;
+            const TEN: Self = 10;
+
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_sub(self, rhs)
+            }
+
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_mul(self, rhs)
+            }
+
+            fn checked_div(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_div(self, rhs)
+            }
+
+            fn checked_rem(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_rem(self, rhs)
+            }
+
+            fn rem_euclid(self, m: Self) -> Self {
+                <$t>::rem_euclid(self, m)
+            }
+
+            $(
+                fn mul_mod(self, rhs: Self, m: Self) -> Self {
+                    (self as $wide * rhs as $wide % m as $wide) as $t
+                }
+            )?
+        }
+    )+};
+}
+
+impl_int!(u8 => u16, u16 => u32, u32 => u64, u64 => u128, usize => u128, u128);
+impl_int!(i8 => i16, i16 => i32, i32 => i64, i64 => i128, isize => i128, i128);
+
+impl SignedInt for i8 {}
+impl SignedInt for i16 {}
+impl SignedInt for i32 {}
+impl SignedInt for i64 {}
+impl SignedInt for isize {}
+impl SignedInt for i128 {}
+
+/// `(a + b) % m` without overflowing, for `a` and `b` in `0..m`.
+fn add_mod<T: Int>(a: T, b: T, m: T) -> T {
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+/// Number of decimal digits of `n`, ignoring the sign. `0` has 1 digit.
+pub fn digits<T: Int>(n: T) -> u32 {
+    let mut n = n;
+    let mut digits = 1;
+    while n / T::TEN != T::ZERO {
+        n = n / T::TEN;
+        digits += 1;
+    }
+    digits
+}
+
+/// `10^exp`.
+pub fn pow10<T: Int>(exp: u32) -> T {
+    checked_pow10(exp).expect("attempt to multiply with overflow")
+}
+
+pub fn checked_pow10<T: Int>(exp: u32) -> Option<T> {
+    (0..exp).try_fold(T::ONE, |p, _| p.checked_mul(T::TEN))
+}
+
+/// The digits of `a` followed by those of `b`, e.g. `concat(12, 345) == 12345`.
+/// `b` must not be negative.
+pub fn concat<T: Int>(a: T, b: T) -> T {
+    checked_concat(a, b).expect("attempt to concatenate with overflow")
+}
+
+pub fn checked_concat<T: Int>(a: T, b: T) -> Option<T> {
+    a.checked_mul(checked_pow10(digits(b))?)?.checked_add(b)
+}
+
+/// The inverse of [`concat`]: `a` such that `concat(a, b) == n`, if `n` ends with the digits of
+/// `b`. Both must not be negative. `deconcat(5, 5)` is `Some(0)`.
+pub fn deconcat<T: Int>(n: T, b: T) -> Option<T> {
+    if n < b {
+        return None;
+    }
+    // 10^digits(b) overflows only if it exceeds n, which then has no digits in front of b.
+    let Some(p) = checked_pow10::<T>(digits(b)) else {
+        return (n == b).then_some(T::ZERO);
+    };
+    ((n - b) % p == T::ZERO).then(|| (n - b) / p)
+}
+
+/// Greatest common divisor, never negative. `gcd(0, 0) == 0`.
+pub fn gcd<T: Int>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a, b);
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    if a < T::ZERO {
+        T::ZERO - a
+    } else {
+        a
+    }
+}
+
+/// Least common multiple, never negative. `lcm(0, n) == 0`.
+pub fn lcm<T: Int>(a: T, b: T) -> T {
+    checked_lcm(a, b).expect("attempt to multiply with overflow")
+}
+
+pub fn checked_lcm<T: Int>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+    let lcm = (a / gcd(a, b)).checked_mul(b)?;
+    Some(if lcm < T::ZERO { T::ZERO - lcm } else { lcm })
+}
+
+/// `(g, x, y)` with `g = gcd(a, b) = a * x + b * y`.
+pub fn extended_gcd<T: SignedInt>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::ONE, T::ZERO);
+    let (mut old_y, mut y) = (T::ZERO, T::ONE);
+
+    while r != T::ZERO {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+
+    if old_r < T::ZERO {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// `x` in `0..m` with `a * x % m == 1`, if `a` and `m` are coprime.
+pub fn mod_inverse<T: SignedInt>(a: T, m: T) -> Option<T> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+    (g == T::ONE).then(|| x.rem_euclid(m))
+}
+
+/// `base^exp % m`, in `0..m`. `exp` must not be negative, `m` must be positive.
+pub fn mod_pow<T: Int>(base: T, exp: T, m: T) -> T {
+    assert!(exp >= T::ZERO, "negative exponent {exp}");
+    let (mut base, mut exp) = (base.rem_euclid(m), exp);
+    let mut result = T::ONE.rem_euclid(m);
+    while exp > T::ZERO {
+        if exp % T::TWO == T::ONE {
+            result = result.mul_mod(base, m);
+        }
+        base = base.mul_mod(base, m);
+        exp = exp / T::TWO;
+    }
+    result
+}
+
+/// Chinese remainder theorem: `(x, m)` such that the numbers `n` with `n % modulus == residue`
+/// for all `(residue, modulus)` pairs are exactly those with `n % m == x`, `x` in `0..m`.
+/// Moduli must be positive but need not be coprime. `None` if there is no such number.
+pub fn crt<T: SignedInt>(congruences: &[(T, T)]) -> Option<(T, T)> {
+    congruences
+        .iter()
+        .try_fold((T::ZERO, T::ONE), |(r1, m1), &(r2, m2)| {
+            let g = gcd(m1, m2);
+            let lcm = (m1 / g)
+                .checked_mul(m2)
+                .expect("attempt to multiply with overflow");
+            combine(r1, m1, r2, m2, g, lcm)
+        })
+}
+
+/// Like [`crt`], but also `None` if the combined modulus overflows.
+pub fn checked_crt<T: SignedInt>(congruences: &[(T, T)]) -> Option<(T, T)> {
+    congruences
+        .iter()
+        .try_fold((T::ZERO, T::ONE), |(r1, m1), &(r2, m2)| {
+            let g = gcd(m1, m2);
+            let lcm = (m1 / g).checked_mul(m2)?;
+            combine(r1, m1, r2, m2, g, lcm)
+        })
+}
+
+/// Merge `x ≡ r1 (mod m1)` with `x ≡ r2 (mod m2)`, where `r1` is already in `0..m1`.
+fn combine<T: SignedInt>(r1: T, m1: T, r2: T, m2: T, g: T, lcm: T) -> Option<(T, T)> {
+    let diff = r2.rem_euclid(m2) - r1.rem_euclid(m2);
+    if diff % g != T::ZERO {
+        return None;
+    }
+    // x = r1 + m1 * k with m1 * k ≡ r2 - r1 (mod m2).
+    let m = m2 / g;
+    let k = (diff / g).rem_euclid(m).mul_mod(mod_inverse(m1 / g, m)?, m);
+    Some((r1 + m1 * k, lcm))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_digits() {
+        assert_eq!(digits(0u8), 1);
+        assert_eq!(digits(9u32), 1);
+        assert_eq!(digits(10u32), 2);
+        assert_eq!(digits(-12345i64), 5);
+        assert_eq!(digits(u128::MAX), 39);
+        assert_eq!(pow10::<u64>(3), 1000);
+        assert_eq!(checked_pow10::<u8>(3), None);
+    }
+
+    #[test]
+    fn concatenates() {
+        assert_eq!(concat(12u64, 345), 12345);
+        assert_eq!(concat(7u32, 0), 70);
+        assert_eq!(checked_concat(25u8, 6), None);
+        assert_eq!(checked_concat(25u8, 5), Some(255));
+
+        assert_eq!(deconcat(12345u64, 345), Some(12));
+        assert_eq!(deconcat(12345u64, 45), Some(123));
+        assert_eq!(deconcat(12345u64, 46), None);
+        assert_eq!(deconcat(5u64, 5), Some(0));
+        assert_eq!(deconcat(5u64, 15), None);
+        assert_eq!(deconcat(200u8, 200), Some(0));
+        assert_eq!(deconcat(u8::MAX, 55), Some(2));
+    }
+
+    #[test]
+    fn computes_divisors() {
+        assert_eq!(gcd(12u32, 18), 6);
+        assert_eq!(gcd(-12i32, 18), 6);
+        assert_eq!(gcd(0u8, 0), 0);
+        assert_eq!(lcm(4u64, 6), 12);
+        assert_eq!(lcm(-4i64, 6), 12);
+        assert_eq!(lcm(0u8, 5), 0);
+        assert_eq!(checked_lcm(200u8, 3), None);
+
+        let (g, x, y) = extended_gcd(240i64, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+    }
+
+    #[test]
+    fn does_modular_arithmetic() {
+        assert_eq!(mod_inverse(3i32, 11), Some(4));
+        assert_eq!(mod_inverse(-3i32, 11), Some(7));
+        assert_eq!(mod_inverse(4i32, 8), None);
+
+        assert_eq!(mod_pow(2u32, 10, 1000), 24);
+        assert_eq!(mod_pow(-2i32, 3, 5), 2);
+        assert_eq!(mod_pow(5u8, 0, 1), 0);
+        // products overflow u64 and even u128.
+        assert_eq!(mod_pow(3u64, 200, u64::MAX - 58), 13293435361704887469);
+        let m = u128::MAX - 158;
+        assert_eq!(mod_pow(m - 1, 2, m), 1);
+        assert_eq!(mod_pow(m - 1, 3, m), m - 1);
+    }
+
+    #[test]
+    fn solves_congruences() {
+        assert_eq!(crt(&[(2i64, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(1i64, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1i64, 4), (2, 6)]), None);
+        assert_eq!(crt(&[(-1i32, 5)]), Some((4, 5)));
+        assert_eq!(crt::<i32>(&[]), Some((0, 1)));
+        assert_eq!(checked_crt(&[(0i8, 11), (0, 13)]), None);
+        assert_eq!(checked_crt(&[(1i8, 11), (1, 7)]), Some((1, 77)));
+    }
+}
@@ -0,0 +1,322 @@
+//! Input parsing helpers that report where the input is malformed.
+//!
+//! Parsing works on [`Span`]s, slices of the input that remember their line and column. Errors
+//! carry that position, e.g. `line 3, column 7: invalid digit found in string ("1x")`.
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// Error with the 1-based line and column it occurred at.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub type Result<T> = std::result::Result<T, ParseError>;
+
+/// A piece of the input, at a 1-based line and column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span<'a> {
+    pub text: &'a str,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl<'a> Span<'a> {
+    /// An error pointing at the start of this span.
+    pub fn error(&self, message: impl Display) -> ParseError {
+        ParseError {
+            line: self.line,
+            column: self.column,
+            message: message.to_string(),
+        }
+    }
+
+    /// The part of this span that `sub` covers. `sub` must be a slice of `self.text`.
+    fn sub(&self, sub: &'a str) -> Span<'a> {
+        let offset = sub.as_ptr() as usize - self.text.as_ptr() as usize;
+        Span {
+            text: sub,
+            line: self.line,
+            column: self.column + self.text[..offset].chars().count(),
+        }
+    }
+
+    /// The span without leading and trailing whitespace.
+    pub fn trim(&self) -> Span<'a> {
+        self.sub(self.text.trim())
+    }
+
+    /// Parse the trimmed span as a `T`.
+    pub fn parse<T>(&self) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let span = self.trim();
+        span.text
+            .parse()
+            .map_err(|e| span.error(format!("{e} ({:?})", span.text)))
+    }
+
+    /// All integers in the span, in order. A `-` directly in front of digits is a sign, unless
+    /// it follows another digit, as in ranges like `1-3`.
+    pub fn ints<T>(&self) -> Result<Vec<T>>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let bytes = self.text.as_bytes();
+        let mut out = vec![];
+        let mut i = 0;
+
+        while i < bytes.len() {
+            let signed = bytes[i] == b'-'
+                && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)
+                && (i == 0 || !bytes[i - 1].is_ascii_digit());
+            if !signed && !bytes[i].is_ascii_digit() {
+                i += 1;
+                continue;
+            }
+
+            let start = i;
+            i += 1;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+            out.push(self.sub(&self.text[start..i]).parse()?);
+        }
+
+        Ok(out)
+    }
+
+    /// Split on every occurrence of `separator`.
+    pub fn split(&self, separator: &str) -> Vec<Span<'a>> {
+        self.text.split(separator).map(|s| self.sub(s)).collect()
+    }
+
+    pub fn split_whitespace(&self) -> Vec<Span<'a>> {
+        self.text.split_whitespace().map(|s| self.sub(s)).collect()
+    }
+
+    /// Split on the first occurrence of `separator`, which must be present.
+    pub fn split_once(&self, separator: &str) -> Result<(Span<'a>, Span<'a>)> {
+        let (left, right) = self
+            .text
+            .split_once(separator)
+            .ok_or_else(|| self.error(format!("expected {separator:?}")))?;
+        Ok((self.sub(left), self.sub(right)))
+    }
+
+    /// Parse the fields separated by `separator` into a tuple, e.g. `(u32, u32)` for `47|53`.
+    pub fn record<R: Record>(&self, separator: &str) -> Result<R> {
+        R::from_fields(self, &self.split(separator))
+    }
+
+    /// Parse whitespace separated fields into a tuple, e.g. `(u32, u32)` for `3   4`.
+    pub fn words<R: Record>(&self) -> Result<R> {
+        R::from_fields(self, &self.split_whitespace())
+    }
+
+    /// Parse every field separated by `separator`.
+    pub fn list<T>(&self, separator: &str) -> Result<Vec<T>>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.split(separator).iter().map(Span::parse).collect()
+    }
+}
+
+/// A fixed number of fields parsed into a tuple.
+pub trait Record: Sized {
+    fn from_fields(span: &Span, fields: &[Span]) -> Result<Self>;
+}
+
+macro_rules! impl_record {
+    ($len:expr; $($t:ident $i:tt),+) => {
+        impl<$($t),+> Record for ($($t,)+)
+        where
+            $($t: FromStr, $t::Err: Display,)+
+        {
+            fn from_fields(span: &Span, fields: &[Span]) -> Result<Self> {
+                if fields.len() != $len {
+                    return Err(span.error(format!(
+                        "expected {} fields, found {}",
+                        $len,
+                        fields.len()
+                    )));
+                }
+                Ok(($(fields[$i].parse::<$t>()?,)+))
+            }
+        }
+    };
+}
+
+impl_record!(1; A 0);
+impl_record!(2; A 0, B 1);
+impl_record!(3; A 0, B 1, C 2);
+impl_record!(4; A 0, B 1, C 2, D 3);
+impl_record!(5; A 0, B 1, C 2, D 3, E 4);
+
+/// Non-blank lines of the input.
+pub fn lines(input: &str) -> impl Iterator<Item = Span<'_>> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| Span {
+            text: line,
+            line: i + 1,
+            column: 1,
+        })
+}
+
+/// Blocks of non-blank lines, separated by one or more blank lines.
+pub fn sections(input: &str) -> Vec<Vec<Span<'_>>> {
+    let mut sections: Vec<Vec<Span>> = vec![];
+    let mut previous = None;
+
+    for line in lines(input) {
+        match sections.last_mut() {
+            Some(section) if previous == Some(line.line - 1) => section.push(line),
+            _ => sections.push(vec![line]),
+        }
+        previous = Some(line.line);
+    }
+
+    sections
+}
+
+/// Exactly `N` blank-line separated sections.
+pub fn sections_n<const N: usize>(input: &str) -> Result<[Vec<Span<'_>>; N]> {
+    let sections = sections(input);
+    let found = sections.len();
+    sections.try_into().map_err(|_| ParseError {
+        line: 1,
+        column: 1,
+        message: format!("expected {N} sections separated by blank lines, found {found}"),
+    })
+}
+
+/// Parse whitespace separated columns of values, e.g. the two lists of `3   4`.
+/// Every line must have exactly `N` values.
+pub fn columns<T, const N: usize>(input: &str) -> Result<[Vec<T>; N]>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let mut columns: [Vec<T>; N] = std::array::from_fn(|_| vec![]);
+
+    for line in lines(input) {
+        let fields = line.split_whitespace();
+        if fields.len() != N {
+            return Err(line.error(format!("expected {N} columns, found {}", fields.len())));
+        }
+        for (column, field) in columns.iter_mut().zip(&fields) {
+            column.push(field.parse()?);
+        }
+    }
+
+    Ok(columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(text: &str) -> Span<'_> {
+        Span {
+            text,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    #[test]
+    fn extracts_ints() {
+        assert_eq!(
+            span("x=-3..5, y=12-14 -").ints::<i64>(),
+            Ok(vec![-3, 5, 12, 14])
+        );
+        assert_eq!(span("190: 10 19").ints::<u64>(), Ok(vec![190, 10, 19]));
+        assert_eq!(span("no numbers").ints::<u8>(), Ok(vec![]));
+
+        let error = span("a 1 -2").ints::<u32>().unwrap_err();
+        assert_eq!((error.line, error.column), (1, 5));
+    }
+
+    #[test]
+    fn splits_sections() {
+        let input = "a\nb\n\n\nc\n\nd\n";
+        let sections = sections(input);
+        let texts: Vec<Vec<&str>> = sections
+            .iter()
+            .map(|s| s.iter().map(|l| l.text).collect())
+            .collect();
+        assert_eq!(texts, [vec!["a", "b"], vec!["c"], vec!["d"]]);
+        assert_eq!(sections[1][0].line, 5);
+
+        assert!(sections_n::<3>(input).is_ok());
+        assert_eq!(
+            sections_n::<2>(input).unwrap_err().to_string(),
+            "line 1, column 1: expected 2 sections separated by blank lines, found 3"
+        );
+    }
+
+    #[test]
+    fn parses_records() {
+        assert_eq!(span("47|53").record::<(u32, u32)>("|"), Ok((47, 53)));
+        assert_eq!(
+            span("move 3 from 1").words::<(String, u8, String, u8)>(),
+            Ok(("move".into(), 3, "from".into(), 1))
+        );
+        assert_eq!(
+            span("1|2|3").record::<(u8, u8)>("|").unwrap_err().message,
+            "expected 2 fields, found 3"
+        );
+        assert_eq!(span("75,47 ,61").list::<u8>(","), Ok(vec![75, 47, 61]));
+    }
+
+    #[test]
+    fn reports_positions() {
+        let line = lines("\n1|2\n3|x4").nth(1).unwrap();
+        let error = line.record::<(u32, u32)>("|").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 3, column 3: invalid digit found in string (\"x4\")"
+        );
+
+        let (left, right) = span("ab: cd").split_once(":").unwrap();
+        assert_eq!((left.text, right.column), ("ab", 4));
+        assert_eq!(right.trim().column, 5);
+        assert!(span("ab").split_once(":").is_err());
+    }
+
+    #[test]
+    fn parses_columns() {
+        let [left, right] = columns::<u32, 2>("3   4\n4   3\n").unwrap();
+        assert_eq!((left, right), (vec![3, 4], vec![4, 3]));
+
+        let error = columns::<u32, 2>("1 2\n3\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 1: expected 2 columns, found 1"
+        );
+    }
+}
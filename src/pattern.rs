@@ -0,0 +1,323 @@
+//! Matching small 2D templates against a character grid.
+//!
+//! Templates are written as grids, `.` matches any cell:
+//!
+//! ```text
+//! M.S
+//! .A.
+//! M.S
+//! ```
+//!
+//! A [`Matcher`] tries every rotation and reflection of its templates, e.g.
+//! `Matcher::parse(&["M.S\n.A.\nM.S"])?.count(&grid)` counts the X-shaped `MAS` crossings.
+use crate::grid::Grid;
+
+pub const WILDCARD: char = '.';
+
+/// A template: the characters it requires at `(row, col)` offsets within its bounding box.
+/// Wildcard cells are not stored.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern {
+    width: usize,
+    height: usize,
+    cells: Vec<((usize, usize), char)>,
+}
+
+impl Pattern {
+    /// Parse a rectangular template, [`WILDCARD`] cells match anything.
+    pub fn parse(template: &str) -> Result<Self, String> {
+        Pattern::parse_with_wildcard(template, WILDCARD)
+    }
+
+    pub fn parse_with_wildcard(template: &str, wildcard: char) -> Result<Self, String> {
+        let grid = Grid::parse(template)?;
+        let cells = grid
+            .iter()
+            .filter(|&(_, &ch)| ch != wildcard)
+            .map(|(pos, &ch)| (pos, ch))
+            .collect();
+        Ok(Pattern {
+            width: grid.width(),
+            height: grid.height(),
+            cells,
+        })
+    }
+
+    /// A `width` × `height` pattern requiring the given characters at `(row, col)` offsets.
+    pub fn from_cells(
+        width: usize,
+        height: usize,
+        cells: impl IntoIterator<Item = ((usize, usize), char)>,
+    ) -> Self {
+        let cells: Vec<_> = cells.into_iter().collect();
+        assert!(
+            cells.iter().all(|&((r, c), _)| r < height && c < width),
+            "cells must be within the {width}x{height} pattern"
+        );
+        Pattern {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// `word` written left to right.
+    pub fn word(word: &str) -> Self {
+        let cells: Vec<_> = word
+            .chars()
+            .enumerate()
+            .map(|(i, ch)| ((0, i), ch))
+            .collect();
+        Pattern {
+            width: cells.len(),
+            height: 1,
+            cells,
+        }
+    }
+
+    /// `word` written diagonally, from the top left to the bottom right.
+    pub fn diagonal(word: &str) -> Self {
+        let cells: Vec<_> = word
+            .chars()
+            .enumerate()
+            .map(|(i, ch)| ((i, i), ch))
+            .collect();
+        Pattern {
+            width: cells.len(),
+            height: cells.len(),
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Required cells as `(row, col)` offsets, in the order they were written in the template.
+    pub fn cells(&self) -> &[((usize, usize), char)] {
+        &self.cells
+    }
+
+    /// Rotate by 90° clockwise.
+    pub fn rotate_cw(&self) -> Self {
+        let height = self.height;
+        self.transform(self.height, self.width, |(r, c)| (c, height - 1 - r))
+    }
+
+    /// Mirror left to right.
+    pub fn flip_horizontal(&self) -> Self {
+        let width = self.width;
+        self.transform(self.width, self.height, |(r, c)| (r, width - 1 - c))
+    }
+
+    fn transform(
+        &self,
+        width: usize,
+        height: usize,
+        f: impl Fn((usize, usize)) -> (usize, usize),
+    ) -> Self {
+        Pattern {
+            width,
+            height,
+            cells: self.cells.iter().map(|&(pos, ch)| (f(pos), ch)).collect(),
+        }
+    }
+
+    /// The 4 rotations, clockwise starting with the pattern itself. Symmetric patterns repeat.
+    pub fn rotations(&self) -> [Pattern; 4] {
+        let mut rotation = self.clone();
+        std::array::from_fn(|_| {
+            let current = rotation.rotate_cw();
+            std::mem::replace(&mut rotation, current)
+        })
+    }
+
+    /// The distinct rotations and reflections of the pattern, between 1 and 8 of them.
+    pub fn orientations(&self) -> Vec<Pattern> {
+        let mut orientations: Vec<Pattern> = vec![];
+        let reflected = self.flip_horizontal();
+        for pattern in self.rotations().into_iter().chain(reflected.rotations()) {
+            if !orientations.iter().any(|o| o.same_shape(&pattern)) {
+                orientations.push(pattern);
+            }
+        }
+        orientations
+    }
+
+    /// Whether both patterns match the same cells, regardless of the order they were written in.
+    fn same_shape(&self, other: &Pattern) -> bool {
+        let sorted = |pattern: &Pattern| {
+            let mut cells = pattern.cells.clone();
+            cells.sort_unstable();
+            cells
+        };
+        (self.width, self.height) == (other.width, other.height) && sorted(self) == sorted(other)
+    }
+
+    /// Whether the pattern matches with its top left corner at `(row, col)`.
+    pub fn matches_at(&self, grid: &Grid<char>, (row, col): (usize, usize)) -> bool {
+        self.cells
+            .iter()
+            .all(|&((r, c), ch)| grid.get((row + r) as isize, (col + c) as isize) == Some(&ch))
+    }
+
+    /// Top left corners of all matches, in reading order.
+    pub fn find<'a>(&'a self, grid: &'a Grid<char>) -> impl Iterator<Item = (usize, usize)> + 'a {
+        let rows = (grid.height() + 1).saturating_sub(self.height);
+        let cols = (grid.width() + 1).saturating_sub(self.width);
+        (0..rows)
+            .flat_map(move |row| (0..cols).map(move |col| (row, col)))
+            .filter(move |&pos| self.matches_at(grid, pos))
+    }
+
+    /// Grid positions of the required cells for a match at `(row, col)`, in template order.
+    pub fn positions_at(&self, (row, col): (usize, usize)) -> Vec<(usize, usize)> {
+        self.cells
+            .iter()
+            .map(|&((r, c), _)| (row + r, col + c))
+            .collect()
+    }
+}
+
+/// One match of a [`Matcher`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Match {
+    /// Index into [`Matcher::patterns`] of the orientation that matched.
+    pub pattern: usize,
+    /// Top left corner of the pattern.
+    pub pos: (usize, usize),
+    /// Positions of the pattern's required cells, in template order.
+    pub cells: Vec<(usize, usize)>,
+}
+
+/// Searches a grid for several patterns at once.
+#[derive(Clone, Debug)]
+pub struct Matcher {
+    patterns: Vec<Pattern>,
+}
+
+impl Matcher {
+    /// Match every distinct rotation and reflection of `patterns`.
+    pub fn new(patterns: impl IntoIterator<Item = Pattern>) -> Self {
+        let mut orientations: Vec<Pattern> = vec![];
+        for pattern in patterns {
+            for pattern in pattern.orientations() {
+                if !orientations.iter().any(|o| o.same_shape(&pattern)) {
+                    orientations.push(pattern);
+                }
+            }
+        }
+        Matcher::exact(orientations)
+    }
+
+    /// Parse templates with [`Pattern::parse`] and match all their orientations.
+    pub fn parse(templates: &[&str]) -> Result<Self, String> {
+        let patterns: Vec<Pattern> = templates
+            .iter()
+            .map(|template| Pattern::parse(template))
+            .collect::<Result<_, _>>()?;
+        Ok(Matcher::new(patterns))
+    }
+
+    /// Match `patterns` only in the orientation they are given in.
+    pub fn exact(patterns: impl IntoIterator<Item = Pattern>) -> Self {
+        Matcher {
+            patterns: patterns.into_iter().collect(),
+        }
+    }
+
+    pub fn patterns(&self) -> &[Pattern] {
+        &self.patterns
+    }
+
+    /// All matches, in reading order of their top left corner.
+    pub fn find(&self, grid: &Grid<char>) -> Vec<Match> {
+        let mut matches: Vec<Match> = self
+            .patterns
+            .iter()
+            .enumerate()
+            .flat_map(|(i, pattern)| {
+                pattern.find(grid).map(move |pos| Match {
+                    pattern: i,
+                    pos,
+                    cells: pattern.positions_at(pos),
+                })
+            })
+            .collect();
+        matches.sort_by_key(|m| (m.pos, m.pattern));
+        matches
+    }
+
+    pub fn count(&self, grid: &Grid<char>) -> usize {
+        self.patterns.iter().map(|p| p.find(grid).count()).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_templates() {
+        let pattern = Pattern::parse("M.S\n.A.").unwrap();
+        assert_eq!((pattern.width(), pattern.height()), (3, 2));
+        assert_eq!(
+            pattern.cells(),
+            [((0, 0), 'M'), ((0, 2), 'S'), ((1, 1), 'A')]
+        );
+        assert!(Pattern::parse("ab\nc").is_err());
+        let pattern = Pattern::parse_with_wildcard("a?", '?').unwrap();
+        assert_eq!(
+            (pattern.width(), pattern.cells()),
+            (2, &[((0, 0), 'a')][..])
+        );
+    }
+
+    #[test]
+    fn transforms_patterns() {
+        let pattern = Pattern::word("AB");
+        let rotated = pattern.rotate_cw();
+        assert_eq!((rotated.width(), rotated.height()), (1, 2));
+        assert_eq!(rotated.cells(), [((0, 0), 'A'), ((1, 0), 'B')]);
+        assert_eq!(
+            pattern.flip_horizontal().cells(),
+            [((0, 1), 'A'), ((0, 0), 'B')]
+        );
+        assert_eq!(pattern.rotations()[2], pattern.flip_horizontal());
+    }
+
+    #[test]
+    fn finds_distinct_orientations() {
+        assert_eq!(Pattern::word("AB").orientations().len(), 4);
+        assert_eq!(Pattern::word("ABA").orientations().len(), 2);
+        assert_eq!(Pattern::diagonal("AB").orientations().len(), 4);
+        assert_eq!(Pattern::parse("AB\nC.").unwrap().orientations().len(), 8);
+        assert_eq!(
+            Pattern::parse("M.S\n.A.\nM.S")
+                .unwrap()
+                .orientations()
+                .len(),
+            4
+        );
+        assert_eq!(Pattern::word("A").orientations().len(), 1);
+    }
+
+    #[test]
+    fn matches_grids() {
+        let grid = Grid::parse("MMS\nXAX\nMSS").unwrap();
+        let matcher = Matcher::parse(&["M.S\n.A.\nM.S"]).unwrap();
+        let matches = matcher.find(&grid);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].pos, (0, 0));
+        assert_eq!(matches[0].cells, [(0, 0), (0, 2), (1, 1), (2, 0), (2, 2)]);
+
+        let words = Matcher::new([Pattern::word("XA"), Pattern::diagonal("XA")]);
+        assert_eq!(words.count(&grid), 2);
+        assert_eq!(Matcher::exact([Pattern::word("AX")]).count(&grid), 1);
+        assert_eq!(Matcher::exact([Pattern::word("SSSS")]).count(&grid), 0);
+    }
+}
@@ -0,0 +1,299 @@
+//! Random inputs and property-based differential testing.
+//!
+//! Days can provide generators for random, valid puzzle inputs and compare a fast solution
+//! against a brute-force reference with [`differential`]. Failing inputs are shrunk to a minimal
+//! example before they are reported.
+//!
+//! The number of cases and the seed default to [`DEFAULT_CASES`] and [`DEFAULT_SEED`] so runs
+//! are reproducible, and can be overridden with the `PROPERTY_CASES` and `PROPERTY_SEED`
+//! environment variables, e.g. `PROPERTY_CASES=100000 cargo test --bin 02`.
+use std::{env, fmt::Debug, ops::RangeInclusive};
+
+pub const DEFAULT_CASES: usize = 1000;
+pub const DEFAULT_SEED: u64 = 0x2024_1201;
+
+/// Small, fast, seedable pseudo random number generator (SplitMix64).
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `range`.
+    pub fn range(&mut self, range: RangeInclusive<i64>) -> i64 {
+        let (start, end) = (*range.start(), *range.end());
+        assert!(start <= end, "empty range {start}..={end}");
+        let span = end.abs_diff(start).wrapping_add(1);
+        if span == 0 {
+            // the range covers all of i64.
+            return self.next_u64() as i64;
+        }
+        start.wrapping_add((self.next_u64() % span) as i64)
+    }
+
+    /// Uniform index in `range`.
+    pub fn usize(&mut self, range: RangeInclusive<usize>) -> usize {
+        self.range(*range.start() as i64..=*range.end() as i64) as usize
+    }
+
+    /// `true` with the given probability.
+    pub fn chance(&mut self, probability: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < probability
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.usize(0..=items.len() - 1)]
+    }
+
+    /// A vector with a length in `len`, filled by `item`.
+    pub fn vec<T>(
+        &mut self,
+        len: RangeInclusive<usize>,
+        mut item: impl FnMut(&mut Rng) -> T,
+    ) -> Vec<T> {
+        let len = self.usize(len);
+        (0..len).map(|_| item(self)).collect()
+    }
+}
+
+/// How many cases to run and how to seed them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Config {
+    pub cases: usize,
+    pub seed: u64,
+    /// Upper bound of shrinking steps, in case a shrinker does not converge.
+    pub max_shrinks: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            cases: DEFAULT_CASES,
+            seed: DEFAULT_SEED,
+            max_shrinks: 10_000,
+        }
+    }
+}
+
+impl Config {
+    /// The default config, overridden by `PROPERTY_CASES` and `PROPERTY_SEED` if set.
+    pub fn from_env() -> Self {
+        let var = |name| env::var(name).ok()?.parse().ok();
+        let default = Config::default();
+        Config {
+            cases: var("PROPERTY_CASES").unwrap_or(default.cases as u64) as usize,
+            seed: var("PROPERTY_SEED").unwrap_or(default.seed),
+            ..default
+        }
+    }
+}
+
+/// A failing case, after shrinking.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Failure<T> {
+    pub value: T,
+    pub message: String,
+    /// Index of the generated case that failed first.
+    pub case: usize,
+    pub shrinks: usize,
+}
+
+/// Run `property` on `config.cases` generated values. Returns the shrunk failure, if any.
+///
+/// `shrink` proposes smaller variants of a value, the first one that still fails replaces it
+/// until no proposal fails anymore.
+pub fn run<T: Clone>(
+    config: Config,
+    generate: impl Fn(&mut Rng) -> T,
+    shrink: impl Fn(&T) -> Vec<T>,
+    property: impl Fn(&T) -> Result<(), String>,
+) -> Option<Failure<T>> {
+    let mut rng = Rng::new(config.seed);
+
+    for case in 0..config.cases {
+        let value = generate(&mut rng);
+        let Err(message) = property(&value) else {
+            continue;
+        };
+
+        let mut failure = Failure {
+            value,
+            message,
+            case,
+            shrinks: 0,
+        };
+
+        'shrink: while failure.shrinks < config.max_shrinks {
+            for candidate in shrink(&failure.value) {
+                if let Err(message) = property(&candidate) {
+                    failure.value = candidate;
+                    failure.message = message;
+                    failure.shrinks += 1;
+                    continue 'shrink;
+                }
+            }
+            break;
+        }
+
+        return Some(failure);
+    }
+
+    None
+}
+
+/// Like [`run`], but panics with the minimal failing value. Meant to be called from tests.
+pub fn check<T: Clone + Debug>(
+    generate: impl Fn(&mut Rng) -> T,
+    shrink: impl Fn(&T) -> Vec<T>,
+    property: impl Fn(&T) -> Result<(), String>,
+) {
+    let config = Config::from_env();
+    if let Some(failure) = run(config, generate, shrink, property) {
+        panic!(
+            "property failed on case {} (seed {}, shrunk {} times): {}\nminimal input: {:#?}",
+            failure.case, config.seed, failure.shrinks, failure.message, failure.value
+        );
+    }
+}
+
+/// Check that `fast` agrees with the brute-force `reference` on generated values.
+pub fn differential<T: Clone + Debug, R: PartialEq + Debug>(
+    generate: impl Fn(&mut Rng) -> T,
+    shrink: impl Fn(&T) -> Vec<T>,
+    fast: impl Fn(&T) -> R,
+    reference: impl Fn(&T) -> R,
+) {
+    check(generate, shrink, |value| {
+        let (actual, expected) = (fast(value), reference(value));
+        if actual == expected {
+            Ok(())
+        } else {
+            Err(format!("expected {expected:?}, got {actual:?}"))
+        }
+    });
+}
+
+/// Shrink an integer towards `target`.
+pub fn shrink_int(n: i64, target: i64) -> Vec<i64> {
+    if n == target {
+        return vec![];
+    }
+    let mut out = vec![target];
+    let half = target + (n - target) / 2;
+    if half != target && half != n {
+        out.push(half);
+    }
+    let step = n - (n - target).signum();
+    if step != target && step != half {
+        out.push(step);
+    }
+    out
+}
+
+/// Shrink a vector by removing elements, then by shrinking single elements with `element`.
+pub fn shrink_vec<T: Clone>(items: &[T], element: impl Fn(&T) -> Vec<T>) -> Vec<Vec<T>> {
+    let mut out = vec![];
+
+    // remove halves first, then single elements.
+    let mut size = items.len().div_ceil(2);
+    while size > 0 {
+        for start in (0..items.len()).step_by(size) {
+            let mut smaller = items[..start].to_vec();
+            smaller.extend_from_slice(&items[(start + size).min(items.len())..]);
+            out.push(smaller);
+        }
+        size /= 2;
+    }
+
+    for (i, item) in items.iter().enumerate() {
+        for smaller in element(item) {
+            let mut candidate = items.to_vec();
+            candidate[i] = smaller;
+            out.push(candidate);
+        }
+    }
+
+    out
+}
+
+/// Shrink a text input by removing lines.
+pub fn shrink_lines(input: &str) -> Vec<String> {
+    let lines: Vec<&str> = input.lines().collect();
+    shrink_vec(&lines, |_| vec![])
+        .into_iter()
+        .map(|lines| lines.join("\n"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generates_values_in_range() {
+        let mut rng = Rng::new(1);
+        for _ in 0..1000 {
+            assert!((-3..=3).contains(&rng.range(-3..=3)));
+            assert!((2..=4).contains(&rng.usize(2..=4)));
+        }
+        assert!(!rng.chance(0.0));
+        assert!(rng.chance(1.0));
+        assert_eq!(Rng::new(7).next_u64(), Rng::new(7).next_u64());
+    }
+
+    #[test]
+    fn shrinks_failures() {
+        // fails for every vector containing a value >= 10.
+        let failure = run(
+            Config::default(),
+            |rng| rng.vec(0..=20, |rng| rng.range(0..=100)),
+            |v| shrink_vec(v, |&n| shrink_int(n, 0)),
+            |v| {
+                if v.iter().any(|&n| n >= 10) {
+                    Err("too large".into())
+                } else {
+                    Ok(())
+                }
+            },
+        )
+        .unwrap();
+        assert_eq!(failure.value, vec![10]);
+    }
+
+    #[test]
+    fn passes_properties() {
+        let failure = run(
+            Config::default(),
+            |rng| rng.range(-100..=100),
+            |&n| shrink_int(n, 0),
+            |n| {
+                if n.abs() <= 100 {
+                    Ok(())
+                } else {
+                    Err(format!("{n}"))
+                }
+            },
+        );
+        assert_eq!(failure, None);
+    }
+
+    #[test]
+    fn shrinks_lines() {
+        assert_eq!(
+            shrink_lines("a\nb\nc"),
+            vec!["c", "a\nb", "b\nc", "a\nc", "a\nb"]
+        );
+    }
+}
@@ -0,0 +1,396 @@
+//! Sets of integers stored as sorted, disjoint ranges, and piecewise maps between them.
+//!
+//! Both inclusive (`1..=3`) and half-open (`1..4`) ranges are accepted, internally every range is
+//! half-open. Adjacent ranges are merged, so `1..=3` and `4..6` become `1..6`.
+//!
+//! A [`RangeMap`] shifts ranges of values by different offsets, like the seed-to-soil tables of
+//! 2023 day 5, and maps whole ranges at once instead of every value in them.
+use std::ops::{Range, RangeInclusive};
+
+use crate::math::Int;
+
+/// A range of integers that can be converted to a half-open one.
+pub trait IntoRange<T> {
+    /// Panics if an inclusive range ends at the maximum of `T`.
+    fn into_range(self) -> Range<T>;
+}
+
+impl<T: Int> IntoRange<T> for Range<T> {
+    fn into_range(self) -> Range<T> {
+        self
+    }
+}
+
+impl<T: Int> IntoRange<T> for RangeInclusive<T> {
+    fn into_range(self) -> Range<T> {
+        let (start, end) = self.into_inner();
+        let end = end.checked_add(T::ONE).expect("range end too large");
+        start..end
+    }
+}
+
+/// The overlap of two ranges, if they overlap.
+pub fn intersect<T: Int>(a: &Range<T>, b: &Range<T>) -> Option<Range<T>> {
+    let range = a.start.max(b.start)..a.end.min(b.end);
+    (range.start < range.end).then_some(range)
+}
+
+/// A set of integers.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RangeSet<T> {
+    /// Non-empty, sorted, neither overlapping nor adjacent.
+    ranges: Vec<Range<T>>,
+}
+
+impl<T: Int> RangeSet<T> {
+    pub fn new() -> Self {
+        RangeSet { ranges: vec![] }
+    }
+
+    /// The ranges of the set, sorted and merged.
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    /// Number of values in the set.
+    pub fn len(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::ZERO, |len, r| len + (r.end - r.start))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|r| r.start)
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.ranges.last().map(|r| r.end - T::ONE)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= value);
+        self.ranges.get(i).is_some_and(|r| r.start <= value)
+    }
+
+    /// Whether every value of `range` is in the set.
+    pub fn contains_range(&self, range: impl IntoRange<T>) -> bool {
+        let range = range.into_range();
+        if range.start >= range.end {
+            return true;
+        }
+        let i = self.ranges.partition_point(|r| r.end <= range.start);
+        self.ranges
+            .get(i)
+            .is_some_and(|r| r.start <= range.start && range.end <= r.end)
+    }
+
+    /// Add all values of `range`, merging it with the ranges it overlaps or touches.
+    pub fn insert(&mut self, range: impl IntoRange<T>) {
+        let Range { mut start, mut end } = range.into_range();
+        if start >= end {
+            return;
+        }
+        let first = self.ranges.partition_point(|r| r.end < start);
+        let last = self.ranges.partition_point(|r| r.start <= end);
+        if first < last {
+            start = start.min(self.ranges[first].start);
+            end = end.max(self.ranges[last - 1].end);
+        }
+        self.ranges.splice(first..last, [start..end]);
+    }
+
+    /// Remove all values of `range`, splitting the ranges it cuts through.
+    pub fn remove(&mut self, range: impl IntoRange<T>) {
+        let Range { start, end } = range.into_range();
+        if start >= end {
+            return;
+        }
+        let first = self.ranges.partition_point(|r| r.end <= start);
+        let last = self.ranges.partition_point(|r| r.start < end);
+        if first >= last {
+            return;
+        }
+        let left = self.ranges[first].start..start;
+        let right = end..self.ranges[last - 1].end;
+        let rest = [left, right].into_iter().filter(|r| r.start < r.end);
+        self.ranges.splice(first..last, rest);
+    }
+
+    pub fn union(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut union = self.clone();
+        for range in &other.ranges {
+            union.insert(range.clone());
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut ranges = vec![];
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j)) {
+            ranges.extend(intersect(a, b));
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        RangeSet { ranges }
+    }
+
+    /// Values of `self` that are not in `other`.
+    pub fn difference(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut difference = self.clone();
+        for range in &other.ranges {
+            difference.remove(range.clone());
+        }
+        difference
+    }
+
+    /// Split into the values below `at` and the values from `at` on.
+    pub fn split_at(&self, at: T) -> (RangeSet<T>, RangeSet<T>) {
+        let i = self.ranges.partition_point(|r| r.end <= at);
+        let (mut below, mut above) = (self.ranges[..i].to_vec(), self.ranges[i..].to_vec());
+        if let Some(first) = above.first_mut() {
+            if first.start < at {
+                below.push(first.start..at);
+                first.start = at;
+            }
+        }
+        (RangeSet { ranges: below }, RangeSet { ranges: above })
+    }
+
+    /// All values in increasing order.
+    pub fn values(&self) -> impl Iterator<Item = T> + '_ {
+        self.ranges.iter().flat_map(|r| {
+            std::iter::successors(Some(r.start), |&v| Some(v + T::ONE)).take_while(|&v| v < r.end)
+        })
+    }
+}
+
+impl<T: Int, R: IntoRange<T>> FromIterator<R> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = R>>(ranges: I) -> Self {
+        let mut set = RangeSet::new();
+        for range in ranges {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+impl<T: Int> From<Range<T>> for RangeSet<T> {
+    fn from(range: Range<T>) -> Self {
+        RangeSet::from_iter([range])
+    }
+}
+
+impl<T: Int> From<RangeInclusive<T>> for RangeSet<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        RangeSet::from_iter([range])
+    }
+}
+
+/// Maps ranges of source values to ranges of the same length starting elsewhere. Values outside
+/// of all source ranges map to themselves.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RangeMap<T> {
+    /// `(source, destination start)`, sorted by source, not overlapping.
+    entries: Vec<(Range<T>, T)>,
+}
+
+impl<T: Int> RangeMap<T> {
+    pub fn new() -> Self {
+        RangeMap { entries: vec![] }
+    }
+
+    /// Map `source` to the values from `destination` on. Panics if `source` overlaps a range
+    /// that is already mapped.
+    pub fn insert(&mut self, source: impl IntoRange<T>, destination: T) {
+        let source = source.into_range();
+        if source.start >= source.end {
+            return;
+        }
+        let i = self.entries.partition_point(|(s, _)| s.end <= source.start);
+        assert!(
+            self.entries
+                .get(i)
+                .is_none_or(|(s, _)| source.end <= s.start),
+            "{source:?} overlaps a mapped range"
+        );
+        self.entries.insert(i, (source, destination));
+    }
+
+    /// `(source range, destination start)` of every mapped range, sorted by source.
+    pub fn entries(&self) -> &[(Range<T>, T)] {
+        &self.entries
+    }
+
+    pub fn get(&self, value: T) -> T {
+        let i = self.entries.partition_point(|(s, _)| s.end <= value);
+        match self.entries.get(i) {
+            Some((source, destination)) if source.start <= value => {
+                *destination + (value - source.start)
+            }
+            _ => value,
+        }
+    }
+
+    /// The values that `range` maps to.
+    pub fn map_range(&self, range: impl IntoRange<T>) -> RangeSet<T> {
+        let range = range.into_range();
+        let mut mapped = RangeSet::new();
+        let mut unmapped = RangeSet::from(range.clone());
+        let first = self.entries.partition_point(|(s, _)| s.end <= range.start);
+        for (source, destination) in &self.entries[first..] {
+            if source.start >= range.end {
+                break;
+            }
+            if let Some(overlap) = intersect(source, &range) {
+                let start = *destination + (overlap.start - source.start);
+                mapped.insert(start..start + (overlap.end - overlap.start));
+                unmapped.remove(overlap);
+            }
+        }
+        mapped.union(&unmapped)
+    }
+
+    /// The values that the values of `set` map to.
+    pub fn map_set(&self, set: &RangeSet<T>) -> RangeSet<T> {
+        set.ranges.iter().fold(RangeSet::new(), |mapped, range| {
+            mapped.union(&self.map_range(range.clone()))
+        })
+    }
+}
+
+#[cfg(test)]
+// expected ranges are compared as arrays, even when there is just one.
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+    use crate::property;
+
+    #[test]
+    fn merges_ranges() {
+        let mut set: RangeSet<i64> = RangeSet::new();
+        set.insert(1..=3);
+        set.insert(10..12);
+        set.insert(4..6);
+        assert_eq!(set.ranges(), [1..6, 10..12]);
+        set.insert(5..=10);
+        assert_eq!(set.ranges(), [1..12]);
+        set.insert(3..3);
+        assert_eq!(set.ranges(), [1..12]);
+        assert_eq!((set.len(), set.min(), set.max()), (11, Some(1), Some(11)));
+
+        let set: RangeSet<u32> = [7..9, 0..2, 1..3].into_iter().collect();
+        assert_eq!(set.ranges(), [0..3, 7..9]);
+        assert_eq!(set.values().collect::<Vec<_>>(), [0, 1, 2, 7, 8]);
+        assert!(set.contains(2) && set.contains(7) && !set.contains(3) && !set.contains(9));
+        assert!(set.contains_range(0..=2) && !set.contains_range(2..8));
+    }
+
+    #[test]
+    fn removes_ranges() {
+        let mut set = RangeSet::from(0..20);
+        set.remove(5..=7);
+        set.remove(15..30);
+        assert_eq!(set.ranges(), [0..5, 8..15]);
+        set.remove(4..9);
+        assert_eq!(set.ranges(), [0..4, 9..15]);
+        set.remove(-5..100);
+        assert!(set.is_empty());
+        assert_eq!(RangeSet::from(3u8..=5).len(), 3);
+    }
+
+    #[test]
+    fn combines_sets() {
+        let a: RangeSet<i32> = [0..5, 10..15].into_iter().collect();
+        let b: RangeSet<i32> = [3..12, 14..20].into_iter().collect();
+        assert_eq!(a.union(&b).ranges(), [0..20]);
+        assert_eq!(a.intersection(&b).ranges(), [3..5, 10..12, 14..15]);
+        assert_eq!(a.difference(&b).ranges(), [0..3, 12..14]);
+        assert_eq!(b.difference(&a).ranges(), [5..10, 15..20]);
+
+        let (below, above) = a.split_at(12);
+        assert_eq!(
+            (below.ranges(), above.ranges()),
+            (&[0..5, 10..12][..], &[12..15][..])
+        );
+        let (below, above) = a.split_at(5);
+        assert_eq!(
+            (below.ranges(), above.ranges()),
+            (&[0..5][..], &[10..15][..])
+        );
+    }
+
+    #[test]
+    fn agrees_with_hash_sets() {
+        use std::collections::HashSet;
+        let random_ranges = |rng: &mut property::Rng| {
+            rng.vec(0..=4, |rng| {
+                let start = rng.range(0..=30);
+                start..start + rng.range(0..=8)
+            })
+        };
+        let values = |set: &RangeSet<i64>| set.values().collect::<HashSet<_>>();
+
+        property::check(
+            |rng| (random_ranges(rng), random_ranges(rng)),
+            |(a, b)| {
+                let fewer = |ranges: &Vec<Range<i64>>| property::shrink_vec(ranges, |_| vec![]);
+                let smaller_a = fewer(a).into_iter().map(|a| (a, b.clone()));
+                let smaller_b = fewer(b).into_iter().map(|b| (a.clone(), b));
+                smaller_a.chain(smaller_b).collect()
+            },
+            |(a, b)| {
+                let (a, b): (RangeSet<i64>, RangeSet<i64>) =
+                    (a.iter().cloned().collect(), b.iter().cloned().collect());
+                let (va, vb) = (values(&a), values(&b));
+                let checks = [
+                    ("union", values(&a.union(&b)) == &va | &vb),
+                    ("intersection", values(&a.intersection(&b)) == &va & &vb),
+                    ("difference", values(&a.difference(&b)) == &va - &vb),
+                    ("len", a.len() as usize == va.len()),
+                    (
+                        "contains",
+                        (0..40).all(|v| a.contains(v) == va.contains(&v)),
+                    ),
+                ];
+                match checks.iter().find(|(_, ok)| !ok) {
+                    Some((name, _)) => Err(format!("{name} disagrees for {a:?} and {b:?}")),
+                    None => Ok(()),
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn maps_ranges() {
+        // 2023 day 5: seed-to-soil map "50 98 2" and "52 50 48".
+        let mut map = RangeMap::new();
+        map.insert(98..100, 50);
+        map.insert(50..98, 52);
+        assert_eq!(
+            [79, 14, 55, 13, 98].map(|seed| map.get(seed)),
+            [81, 14, 57, 13, 50]
+        );
+        assert_eq!(map.map_range(79..93).ranges(), [81..95]);
+        assert_eq!(map.map_range(45..=99).ranges(), [45..100]);
+        assert_eq!(map.map_range(96..102).ranges(), [50..52, 98..102]);
+
+        let seeds: RangeSet<u64> = [79..93, 55..68].into_iter().collect();
+        assert_eq!(map.map_set(&seeds).ranges(), [57..70, 81..95]);
+    }
+
+    #[test]
+    #[should_panic(expected = "overlaps")]
+    fn rejects_overlapping_maps() {
+        let mut map = RangeMap::new();
+        map.insert(0..10, 20);
+        map.insert(5..15, 40);
+    }
+}
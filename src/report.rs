@@ -0,0 +1,390 @@
+//! Validating sequences of levels, like the reports of day 2.
+//!
+//! [`Rules`] restrict the steps between adjacent levels and their direction, and may allow
+//! removing a few levels to make a sequence valid. [`Rules::check`] explains its verdict: which
+//! levels had to be removed, or the first pair of levels that breaks the rules.
+use std::{
+    fmt::{self, Display},
+    ops::RangeInclusive,
+};
+
+/// Direction that adjacent levels must follow.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// No level is lower than the one before.
+    Increasing,
+    /// No level is higher than the one before.
+    Decreasing,
+    /// Either increasing or decreasing, as decided by the first levels that differ.
+    Monotonic,
+    /// Levels may go up and down.
+    Any,
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Direction::Increasing => "increasing",
+            Direction::Decreasing => "decreasing",
+            Direction::Monotonic => "monotonic",
+            Direction::Any => "any direction",
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rules {
+    /// Allowed absolute differences between adjacent levels.
+    pub steps: RangeInclusive<u64>,
+    pub direction: Direction,
+    /// How many levels may be removed to make the sequence valid.
+    pub max_removals: usize,
+}
+
+/// The rules of day 2, part 1: steps of 1 to 3 in a single direction, no removals.
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            steps: 1..=3,
+            direction: Direction::Monotonic,
+            max_removals: 0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    /// Valid after removing the levels at these indices, in increasing order.
+    Safe {
+        removed: Vec<usize>,
+    },
+    Unsafe(Violation),
+}
+
+impl Verdict {
+    pub fn is_safe(&self) -> bool {
+        matches!(self, Verdict::Safe { .. })
+    }
+}
+
+/// Why a sequence is invalid, pointing at the first offending pair `levels[index]`,
+/// `levels[index + 1]` of the full sequence.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Violation {
+    /// Fewer than 2 levels, which are never valid.
+    TooShort,
+    /// The difference is not in `allowed`.
+    Step {
+        index: usize,
+        pair: (i64, i64),
+        allowed: RangeInclusive<u64>,
+    },
+    /// The pair goes against the `expected` direction.
+    Direction {
+        index: usize,
+        pair: (i64, i64),
+        expected: Direction,
+    },
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Safe { removed } if removed.is_empty() => write!(f, "safe"),
+            Verdict::Safe { removed } => {
+                let indices: Vec<String> = removed.iter().map(usize::to_string).collect();
+                let noun = if removed.len() == 1 {
+                    "index"
+                } else {
+                    "indices"
+                };
+                write!(f, "safe after removing {noun} {}", indices.join(", "))
+            }
+            Verdict::Unsafe(violation) => write!(f, "unsafe: {violation}"),
+        }
+    }
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::TooShort => write!(f, "fewer than 2 levels"),
+            Violation::Step {
+                index,
+                pair: (a, b),
+                allowed,
+            } => write!(
+                f,
+                "{a} -> {b} at index {index} is a step of {}, outside {}..={}",
+                a.abs_diff(*b),
+                allowed.start(),
+                allowed.end()
+            ),
+            Violation::Direction {
+                index,
+                pair: (a, b),
+                expected,
+            } => write!(f, "{a} -> {b} at index {index} is not {expected}"),
+        }
+    }
+}
+
+impl Rules {
+    /// Whether `levels` are valid, possibly after removing up to `max_removals` of them.
+    /// Fewer removals are preferred, at least 2 levels must be left.
+    pub fn check(&self, levels: &[i64]) -> Verdict {
+        let directions: &[Direction] = match self.direction {
+            Direction::Monotonic => &[Direction::Increasing, Direction::Decreasing],
+            ref direction => std::slice::from_ref(direction),
+        };
+
+        let best = directions
+            .iter()
+            .filter_map(|&direction| self.fewest_removals(levels, direction))
+            .min_by_key(Vec::len);
+
+        match best {
+            Some(removed) => Verdict::Safe { removed },
+            None => Verdict::Unsafe(self.first_violation(levels)),
+        }
+    }
+
+    pub fn is_safe(&self, levels: &[i64]) -> bool {
+        self.check(levels).is_safe()
+    }
+
+    fn allows(&self, a: i64, b: i64, direction: Direction) -> bool {
+        let direction_ok = match direction {
+            Direction::Increasing => b >= a,
+            Direction::Decreasing => b <= a,
+            Direction::Monotonic | Direction::Any => true,
+        };
+        direction_ok && self.steps.contains(&a.abs_diff(b))
+    }
+
+    /// Indices to remove so that the rest follows `direction`, if at most `max_removals`.
+    ///
+    /// For every level, finds the fewest removals before it for a valid chain of at least 2 kept
+    /// levels ending there. Only the `max_removals + 1` levels in front can be its predecessor.
+    fn fewest_removals(&self, levels: &[i64], direction: Direction) -> Option<Vec<usize>> {
+        let n = levels.len();
+        // (removals, predecessor) of the best chain of at least 2 levels ending at each index.
+        let mut chains: Vec<Option<(usize, usize)>> = vec![None; n];
+        // a chain through `j` starts there if that removes no more than continuing one.
+        let starts_at = |chains: &[Option<(usize, usize)>], j: usize| {
+            chains[j].is_none_or(|(removals, _)| j <= removals)
+        };
+
+        for i in 1..n {
+            for j in (i.saturating_sub(self.max_removals + 1)..i).rev() {
+                if !self.allows(levels[j], levels[i], direction) {
+                    continue;
+                }
+                let before = match chains[j] {
+                    Some((removals, _)) if !starts_at(&chains, j) => removals,
+                    _ => j,
+                };
+                let removals = before + (i - j - 1);
+                if chains[i].is_none_or(|(best, _)| removals < best) {
+                    chains[i] = Some((removals, j));
+                }
+            }
+        }
+
+        let (removals, last) = (0..n)
+            .filter_map(|i| chains[i].map(|(removals, _)| (removals + (n - 1 - i), i)))
+            .min()?;
+        if removals > self.max_removals {
+            return None;
+        }
+
+        // the last level has a predecessor, earlier ones only if their chain does not start there.
+        let mut kept = vec![false; n];
+        kept[last] = true;
+        let mut current = last;
+        loop {
+            let (_, previous) = chains[current].expect("chains continue from their predecessor");
+            kept[previous] = true;
+            current = previous;
+            if starts_at(&chains, current) {
+                break;
+            }
+        }
+
+        Some((0..n).filter(|&i| !kept[i]).collect())
+    }
+
+    /// The first pair of the full sequence that breaks the rules. For [`Direction::Monotonic`],
+    /// the first pair of different levels sets the direction.
+    fn first_violation(&self, levels: &[i64]) -> Violation {
+        if levels.len() < 2 {
+            return Violation::TooShort;
+        }
+
+        let expected = match self.direction {
+            Direction::Monotonic => match levels.windows(2).find(|w| w[0] != w[1]) {
+                Some(w) if w[1] < w[0] => Direction::Decreasing,
+                _ => Direction::Increasing,
+            },
+            direction => direction,
+        };
+
+        for (index, w) in levels.windows(2).enumerate() {
+            let pair = (w[0], w[1]);
+            if !self.steps.contains(&w[0].abs_diff(w[1])) {
+                return Violation::Step {
+                    index,
+                    pair,
+                    allowed: self.steps.clone(),
+                };
+            }
+            if !self.allows(w[0], w[1], expected) {
+                return Violation::Direction {
+                    index,
+                    pair,
+                    expected,
+                };
+            }
+        }
+
+        unreachable!("levels without violations are safe")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::property;
+
+    fn with_removals(max_removals: usize) -> Rules {
+        Rules {
+            max_removals,
+            ..Rules::default()
+        }
+    }
+
+    /// Whether `levels` follow the rules of day 2 without removals.
+    fn is_valid(levels: &[i64]) -> bool {
+        let follows = |direction| {
+            levels
+                .windows(2)
+                .all(|w| Rules::default().allows(w[0], w[1], direction))
+        };
+        levels.len() >= 2 && (follows(Direction::Increasing) || follows(Direction::Decreasing))
+    }
+
+    /// Reference: the fewest removals of any subset of levels that leaves a valid sequence.
+    fn brute_force(levels: &[i64], max_removals: usize) -> Option<usize> {
+        (0u32..1 << levels.len())
+            .filter(|mask| mask.count_ones() as usize <= max_removals)
+            .filter(|mask| is_valid(&remove(levels, |i| mask & (1 << i) != 0)))
+            .map(|mask| mask.count_ones() as usize)
+            .min()
+    }
+
+    fn remove(levels: &[i64], removed: impl Fn(usize) -> bool) -> Vec<i64> {
+        (0..levels.len())
+            .filter(|&i| !removed(i))
+            .map(|i| levels[i])
+            .collect()
+    }
+
+    #[test]
+    fn checks_reports() {
+        let rules = Rules::default();
+        assert_eq!(
+            rules.check(&[7, 6, 4, 2, 1]),
+            Verdict::Safe { removed: vec![] }
+        );
+        assert_eq!(
+            rules.check(&[1, 2, 7, 8, 9]).to_string(),
+            "unsafe: 2 -> 7 at index 1 is a step of 5, outside 1..=3"
+        );
+        assert_eq!(
+            rules.check(&[1, 3, 2, 4, 5]).to_string(),
+            "unsafe: 3 -> 2 at index 1 is not increasing"
+        );
+        assert_eq!(rules.check(&[1]), Verdict::Unsafe(Violation::TooShort));
+    }
+
+    #[test]
+    fn removes_levels() {
+        let rules = with_removals(1);
+        assert_eq!(
+            rules.check(&[1, 3, 2, 4, 5]),
+            Verdict::Safe { removed: vec![1] }
+        );
+        assert_eq!(
+            rules.check(&[8, 6, 4, 4, 1]).to_string(),
+            "safe after removing index 2"
+        );
+        assert!(!rules.is_safe(&[9, 7, 6, 2, 1]));
+        assert!(!rules.is_safe(&[1, 5]));
+
+        let rules = with_removals(2);
+        assert_eq!(
+            rules.check(&[9, 1, 2, 9, 3]),
+            Verdict::Safe {
+                removed: vec![0, 3]
+            }
+        );
+        assert!(!rules.is_safe(&[1, 9, 9, 2, 9]));
+        assert_eq!(
+            with_removals(3).check(&[1, 9, 9, 2, 9]).to_string(),
+            "safe after removing indices 1, 2, 4"
+        );
+    }
+
+    #[test]
+    fn supports_other_rules() {
+        let rules = Rules {
+            steps: 0..=10,
+            direction: Direction::Decreasing,
+            max_removals: 0,
+        };
+        assert!(rules.is_safe(&[5, 5, 1]));
+        assert_eq!(
+            rules.check(&[5, 6]).to_string(),
+            "unsafe: 5 -> 6 at index 0 is not decreasing"
+        );
+
+        let rules = Rules {
+            direction: Direction::Any,
+            ..Rules::default()
+        };
+        assert!(rules.is_safe(&[1, 3, 2, 4]));
+        assert!(!rules.is_safe(&[1, 1]));
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        property::check(
+            |rng| (rng.vec(0..=8, |rng| rng.range(0..=8)), rng.usize(0..=3)),
+            |(levels, max_removals)| {
+                let fewer = (0..*max_removals).map(|m| (levels.clone(), m));
+                property::shrink_vec(levels, |&n| property::shrink_int(n, 0))
+                    .into_iter()
+                    .map(|levels| (levels, *max_removals))
+                    .chain(fewer)
+                    .collect()
+            },
+            |(levels, max_removals)| {
+                let actual = match with_removals(*max_removals).check(levels) {
+                    Verdict::Safe { removed } => {
+                        if !is_valid(&remove(levels, |i| removed.contains(&i))) {
+                            return Err(format!("removing {removed:?} leaves an invalid report"));
+                        }
+                        Some(removed.len())
+                    }
+                    Verdict::Unsafe(_) => None,
+                };
+                let expected = brute_force(levels, *max_removals);
+                if actual == expected {
+                    Ok(())
+                } else {
+                    Err(format!("expected {expected:?} removals, got {actual:?}"))
+                }
+            },
+        );
+    }
+}
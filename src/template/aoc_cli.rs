@@ -1,6 +1,8 @@
 /// Wrapper module around the "aoc-cli" command-line.
 use std::{
     fmt::Display,
+    io::Write,
+    path::PathBuf,
     process::{Command, Output, Stdio},
 };
 
@@ -11,6 +13,8 @@ pub enum AocCommandError {
     CommandNotFound,
     CommandNotCallable,
     BadExitStatus(Output),
+    SessionNotFound,
+    YearNotSet,
 }
 
 impl Display for AocCommandError {
@@ -21,6 +25,12 @@ impl Display for AocCommandError {
             AocCommandError::BadExitStatus(_) => {
                 write!(f, "aoc-cli exited with a non-zero status.")
             }
+            AocCommandError::SessionNotFound => {
+                write!(f, "could not find an aoc session cookie.")
+            }
+            AocCommandError::YearNotSet => {
+                write!(f, "`AOC_YEAR` is not set.")
+            }
         }
     }
 }
@@ -77,7 +87,89 @@ pub fn submit(day: Day, part: u8, result: &str) -> Result<Output, AocCommandErro
     let mut args = build_args("submit", &[], day);
     args.push(part.to_string());
     args.push(result.to_string());
-    call_aoc_cli(&args)
+    call_aoc_cli_captured(&args)
+}
+
+/// Whether the output of a `submit` call reports the answer as correct.
+pub fn is_accepted(output: &Output) -> bool {
+    String::from_utf8_lossy(&output.stdout).contains("That's the right answer")
+}
+
+/// Fetch the JSON of a private leaderboard.
+/// aoc-cli only renders leaderboards as text, so the client requests the JSON endpoint itself,
+/// authenticating with the session cookie aoc-cli uses. curl reads the cookie from its config
+/// on stdin, so it does not show up in the process list.
+pub fn private_leaderboard(id: u64) -> Result<String, AocCommandError> {
+    let year = get_year().ok_or(AocCommandError::YearNotSet)?;
+    let session = get_session().ok_or(AocCommandError::SessionNotFound)?;
+
+    let mut child = Command::new("curl")
+        .args(["--silent", "--fail", "--config", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|_| AocCommandError::CommandNotCallable)?;
+
+    let url = format!("https://adventofcode.com/{year}/leaderboard/private/view/{id}.json");
+    let config = format!(
+        "cookie = \"session={}\"\nurl = \"{url}\"\n",
+        curl_escape(&session)
+    );
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    stdin
+        .write_all(config.as_bytes())
+        .map_err(|_| AocCommandError::CommandNotCallable)?;
+    drop(stdin);
+
+    let output = child
+        .wait_with_output()
+        .map_err(|_| AocCommandError::CommandNotCallable)?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(AocCommandError::BadExitStatus(output))
+    }
+}
+
+/// Escape a value for a double-quoted string in a curl config file.
+fn curl_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Read the session cookie from the places aoc-cli looks: `ADVENT_OF_CODE_SESSION`,
+/// `~/.adventofcode.session` or `adventofcode.session` in the user's config directory.
+fn get_session() -> Option<String> {
+    if let Ok(session) = std::env::var("ADVENT_OF_CODE_SESSION") {
+        return Some(session.trim().to_string());
+    }
+
+    let home = std::env::var("HOME")
+        .or_else(|_| std::env::var("USERPROFILE"))
+        .ok()
+        .map(PathBuf::from);
+    let candidates = [
+        home.as_ref().map(|home| home.join(".adventofcode.session")),
+        config_dir().map(|dir| dir.join("adventofcode.session")),
+    ];
+    candidates
+        .into_iter()
+        .flatten()
+        .find_map(|path| std::fs::read_to_string(path).ok())
+        .map(|session| session.trim().to_string())
+}
+
+/// The user's config directory, as aoc-cli resolves it.
+fn config_dir() -> Option<PathBuf> {
+    let var = |name| std::env::var_os(name).map(PathBuf::from);
+    if cfg!(windows) {
+        var("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        var("HOME").map(|home| home.join("Library/Application Support"))
+    } else {
+        var("XDG_CONFIG_HOME").or_else(|| var("HOME").map(|home| home.join(".config")))
+    }
 }
 
 fn get_input_path(day: Day) -> String {
@@ -88,7 +180,7 @@ fn get_puzzle_path(day: Day) -> String {
     format!("data/puzzles/{day}.md")
 }
 
-fn get_year() -> Option<u16> {
+pub fn get_year() -> Option<u16> {
     match std::env::var("AOC_YEAR") {
         Ok(x) => x.parse().ok().or(None),
         Err(_) => None,
@@ -123,3 +215,21 @@ fn call_aoc_cli(args: &[String]) -> Result<Output, AocCommandError> {
         Err(AocCommandError::BadExitStatus(output))
     }
 }
+
+/// Like [`call_aoc_cli`], but keeps a copy of stdout so callers can inspect the response.
+fn call_aoc_cli_captured(args: &[String]) -> Result<Output, AocCommandError> {
+    let output = Command::new("aoc")
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|_| AocCommandError::CommandNotCallable)?;
+
+    print!("{}", String::from_utf8_lossy(&output.stdout));
+
+    if output.status.success() {
+        Ok(output)
+    } else {
+        Err(AocCommandError::BadExitStatus(output))
+    }
+}
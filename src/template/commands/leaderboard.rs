@@ -0,0 +1,127 @@
+use std::{env, fs, process};
+
+use crate::template::leaderboard::{Leaderboard, Member};
+use crate::template::stats::{format_duration, unlock_time};
+use crate::template::{all_days, aoc_cli, Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+pub fn handle(id: Option<u64>, file: Option<String>, day: Option<Day>) {
+    let json = match (file, id.or_else(leaderboard_id_from_env)) {
+        (Some(path), _) => fs::read_to_string(&path).map_err(|e| format!("{path}: {e}")),
+        (None, Some(id)) => aoc_cli::private_leaderboard(id).map_err(|e| e.to_string()),
+        (None, None) => Err(
+            "no leaderboard given. Pass `--id <id>`, set `AOC_LEADERBOARD_ID` or pass `--file <path>`."
+                .into(),
+        ),
+    };
+
+    let leaderboard = match json.and_then(|json| Leaderboard::try_from(json.as_str())) {
+        Ok(leaderboard) => leaderboard,
+        Err(e) => {
+            eprintln!("Failed to load leaderboard: {e}");
+            process::exit(1);
+        }
+    };
+
+    match day {
+        Some(day) => print_day(&leaderboard, day),
+        None => print_standings(&leaderboard),
+    }
+}
+
+fn leaderboard_id_from_env() -> Option<u64> {
+    env::var("AOC_LEADERBOARD_ID").ok()?.parse().ok()
+}
+
+fn star_for(member: &Member, day: Day) -> char {
+    match (member.star_ts(day, 1), member.star_ts(day, 2)) {
+        (Some(_), Some(_)) => '★',
+        (Some(_), None) => '☆',
+        _ => '·',
+    }
+}
+
+fn print_standings(leaderboard: &Leaderboard) {
+    println!(
+        "{ANSI_BOLD}Private leaderboard {}{ANSI_RESET} ({} members)",
+        leaderboard.event,
+        leaderboard.members.len()
+    );
+    println!(
+        "{ANSI_ITALIC}★ both stars, ☆ first star only. Scores are recalculated from star timestamps, `AoC` is the score reported by the server.{ANSI_RESET}"
+    );
+    println!();
+    println!(
+        "{ANSI_BOLD}{:>4}  {:>5}  {:>5}  {:>5}  {:<25}  Name{ANSI_RESET}",
+        "#", "Score", "AoC", "Stars", "1        10        20"
+    );
+
+    for (rank, standing) in leaderboard.standings().iter().enumerate() {
+        let stars: String = all_days()
+            .map(|day| star_for(standing.member, day))
+            .collect();
+        println!(
+            "{:>3})  {:>5}  {:>5}  {:>5}  {stars}  {}",
+            rank + 1,
+            standing.score,
+            standing.member.local_score,
+            standing.member.stars,
+            standing.member.name
+        );
+    }
+}
+
+fn print_day(leaderboard: &Leaderboard, day: Day) {
+    let unlocked_at = leaderboard
+        .event
+        .parse()
+        .ok()
+        .map(|year| unlock_time(year, day));
+
+    let since_unlock = |ts: Option<u64>| match (ts, unlocked_at) {
+        (Some(ts), Some(unlocked_at)) => format_duration(ts.saturating_sub(unlocked_at)),
+        (Some(_), None) => "?".into(),
+        (None, _) => "-".into(),
+    };
+
+    let mut members: Vec<&Member> = leaderboard
+        .members
+        .iter()
+        .filter(|m| m.star_ts(day, 1).is_some())
+        .collect();
+    members.sort_by_key(|m| {
+        (
+            m.star_ts(day, 2).is_none(),
+            m.star_ts(day, 2),
+            m.star_ts(day, 1),
+        )
+    });
+
+    println!(
+        "{ANSI_BOLD}Day {day}{ANSI_RESET} of private leaderboard {}",
+        leaderboard.event
+    );
+    println!("------");
+
+    if members.is_empty() {
+        println!("Nobody has solved this day yet.");
+        return;
+    }
+
+    println!(
+        "{ANSI_BOLD}{:>4}  {:<13}{:<13}{:<13}Name{ANSI_RESET}",
+        "#", "Part 1", "Part 2", "Delta"
+    );
+
+    for (rank, member) in members.iter().enumerate() {
+        println!(
+            "{:>3})  {:<13}{:<13}{:<13}{}",
+            rank + 1,
+            since_unlock(member.star_ts(day, 1)),
+            since_unlock(member.star_ts(day, 2)),
+            member
+                .delta(day)
+                .map_or_else(|| "-".into(), format_duration),
+            member.name
+        );
+    }
+}
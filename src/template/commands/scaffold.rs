@@ -1,79 +1,331 @@
 use std::{
-    fs::{File, OpenOptions},
+    fs::{self, OpenOptions},
     io::Write,
+    path::Path,
     process,
 };
 
-use crate::template::Day;
+use crate::template::status::{file_state, FileState};
+use crate::template::templates::{self, TemplateValues};
+use crate::template::{stats, Day};
 
-const MODULE_TEMPLATE: &str =
-    include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template.txt"));
+/// What scaffolding will do with a single file.
+#[derive(Debug, PartialEq, Eq)]
+enum Action {
+    /// The file does not exist yet and will be created with the given contents.
+    Create(String),
+    /// The file exists and will be replaced. Keeps the previous contents for rollback.
+    Overwrite { contents: String, previous: Vec<u8> },
+    /// The file exists and is left untouched.
+    Keep(FileState),
+}
 
-fn safe_create_file(path: &str, overwrite: bool) -> Result<File, std::io::Error> {
-    let mut file = OpenOptions::new();
-    if overwrite {
-        file.create(true);
-    } else {
-        file.create_new(true);
-    }
-    file.truncate(true).write(true).open(path)
+/// A file that is part of a scaffolded day.
+#[derive(Debug)]
+struct Step {
+    label: &'static str,
+    path: String,
+    action: Action,
 }
 
-fn create_file(path: &str) -> Result<File, std::io::Error> {
-    OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(path)
+impl Step {
+    fn describe(&self, dry_run: bool) -> String {
+        let (create, overwrite) = if dry_run {
+            ("Would create", "Would overwrite")
+        } else {
+            ("Created", "Overwrote")
+        };
+        let empty = if self.label == "module" { "" } else { "empty " };
+
+        match &self.action {
+            Action::Create(_) => format!("{create} {empty}{} file \"{}\"", self.label, self.path),
+            Action::Overwrite { .. } => {
+                format!("{overwrite} {} file \"{}\"", self.label, self.path)
+            }
+            Action::Keep(FileState::Empty) => {
+                format!("Kept existing empty {} file \"{}\"", self.label, self.path)
+            }
+            Action::Keep(_) => format!("Kept existing {} file \"{}\"", self.label, self.path),
+        }
+    }
 }
 
-pub fn handle(day: Day, overwrite: bool) {
-    let input_path = format!("data/inputs/{day}.txt");
-    let example_path = format!("data/examples/{day}.txt");
-    let module_path = format!("src/bin/{day}.rs");
+/// Decide what to do with every file below `root` before touching any of them.
+/// The module file is only replaced with `--overwrite`, data files are never replaced.
+fn plan(root: &Path, day: Day, overwrite: bool, module: String) -> Result<Vec<Step>, String> {
+    let path = |relative: String| root.join(relative).to_string_lossy().into_owned();
+    let module_path = path(format!("src/bin/{day}.rs"));
+    let module_action = match fs::read(&module_path) {
+        Ok(previous) if overwrite => Action::Overwrite {
+            contents: module,
+            previous,
+        },
+        Ok(_) => {
+            return Err(format!(
+                "module file \"{module_path}\" already exists. Use `--overwrite` to replace it."
+            ))
+        }
+        Err(_) => Action::Create(module),
+    };
 
-    let mut file = match safe_create_file(&module_path, overwrite) {
-        Ok(file) => file,
-        Err(e) => {
-            eprintln!("Failed to create module file: {e}");
-            process::exit(1);
+    let data_step = |label, path: String| {
+        let action = match file_state(&path) {
+            FileState::Missing => Action::Create(String::new()),
+            state => Action::Keep(state),
+        };
+        Step {
+            label,
+            path,
+            action,
         }
     };
 
-    match file.write_all(
-        MODULE_TEMPLATE
-            .replace("%DAY_NUMBER%", &day.into_inner().to_string())
-            .as_bytes(),
-    ) {
-        Ok(()) => {
-            println!("Created module file \"{}\"", &module_path);
+    Ok(vec![
+        Step {
+            label: "module",
+            path: module_path,
+            action: module_action,
+        },
+        data_step("input", path(format!("data/inputs/{day}.txt"))),
+        data_step("example", path(format!("data/examples/{day}.txt"))),
+    ])
+}
+
+fn apply(step: &Step) -> Result<(), std::io::Error> {
+    match &step.action {
+        Action::Create(contents) => OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&step.path)?
+            .write_all(contents.as_bytes()),
+        Action::Overwrite { contents, .. } => fs::write(&step.path, contents),
+        Action::Keep(_) => Ok(()),
+    }
+}
+
+/// Undo already applied steps, most recent first.
+fn rollback(applied: &[&Step]) {
+    for step in applied.iter().rev() {
+        let result = match &step.action {
+            Action::Create(_) => fs::remove_file(&step.path),
+            Action::Overwrite { previous, .. } => fs::write(&step.path, previous),
+            Action::Keep(_) => Ok(()),
+        };
+        match result {
+            Ok(()) => eprintln!("Rolled back {} file \"{}\"", step.label, step.path),
+            Err(e) => eprintln!(
+                "Failed to roll back {} file \"{}\": {e}",
+                step.label, step.path
+            ),
         }
-        Err(e) => {
-            eprintln!("Failed to write module contents: {e}");
-            process::exit(1);
+    }
+}
+
+/// Apply all steps, or none of them if one fails. With `dry_run`, only describes them.
+fn execute(steps: &[Step], dry_run: bool) -> Result<Vec<String>, String> {
+    if !dry_run {
+        let mut applied: Vec<&Step> = Vec::with_capacity(steps.len());
+        for step in steps {
+            if let Some(dir) = Path::new(&step.path).parent() {
+                // data directories might be missing in fresh clones; creating them is not rolled back.
+                let _ = fs::create_dir_all(dir);
+            }
+            if let Err(e) = apply(step) {
+                rollback(&applied);
+                return Err(format!(
+                    "Failed to write {} file \"{}\": {e}",
+                    step.label, step.path
+                ));
+            }
+            applied.push(step);
         }
     }
+    Ok(steps.iter().map(|step| step.describe(dry_run)).collect())
+}
 
-    match create_file(&input_path) {
-        Ok(_) => {
-            println!("Created empty input file \"{}\"", &input_path);
+pub fn handle(
+    day: Day,
+    overwrite: bool,
+    dry_run: bool,
+    template: &str,
+    examples: [Option<String>; 2],
+) {
+    let module_template = match templates::load(template) {
+        Ok(module_template) => module_template,
+        Err(e) => {
+            eprintln!("Failed to load template: {e}");
+            process::exit(1);
         }
+    };
+    let values = TemplateValues::for_day(day, examples);
+
+    let module = templates::render(&module_template, &values);
+    let steps = match plan(Path::new(""), day, overwrite, module) {
+        Ok(steps) => steps,
         Err(e) => {
-            eprintln!("Failed to create input file: {e}");
+            eprintln!("Failed to scaffold day {day}: {e}");
             process::exit(1);
         }
-    }
+    };
 
-    match create_file(&example_path) {
-        Ok(_) => {
-            println!("Created empty example file \"{}\"", &example_path);
+    match execute(&steps, dry_run) {
+        Ok(descriptions) => {
+            for description in descriptions {
+                println!("{description}");
+            }
         }
         Err(e) => {
-            eprintln!("Failed to create example file: {e}");
+            eprintln!("{e}");
             process::exit(1);
         }
     }
+    if dry_run {
+        return;
+    }
+
+    stats::record(|stats, now| stats.record_scaffold(day, now));
 
     println!("---");
     println!("🎄 Type `cargo solve {day}` to run your solution.");
 }
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use super::{execute, plan, Action, Step};
+    use crate::day;
+    use crate::template::status::FileState;
+
+    /// A fresh directory with the layout of the repository.
+    fn temp_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("aoc-scaffold-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        for dir in ["src/bin", "data/inputs", "data/examples"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn describes_steps() {
+        let step = |label, path: &str, action| Step {
+            label,
+            path: path.into(),
+            action,
+        };
+
+        assert_eq!(
+            step("input", "data/inputs/01.txt", Action::Create(String::new())).describe(false),
+            "Created empty input file \"data/inputs/01.txt\""
+        );
+        assert_eq!(
+            step(
+                "example",
+                "data/examples/01.txt",
+                Action::Keep(FileState::Present)
+            )
+            .describe(false),
+            "Kept existing example file \"data/examples/01.txt\""
+        );
+        assert_eq!(
+            step("module", "src/bin/01.rs", Action::Create("x".into())).describe(true),
+            "Would create module file \"src/bin/01.rs\""
+        );
+        assert_eq!(
+            step(
+                "module",
+                "src/bin/01.rs",
+                Action::Overwrite {
+                    contents: "x".into(),
+                    previous: vec![],
+                }
+            )
+            .describe(true),
+            "Would overwrite module file \"src/bin/01.rs\""
+        );
+    }
+
+    #[test]
+    fn keeps_existing_data_files() {
+        let root = temp_root("keep");
+        fs::write(root.join("data/inputs/01.txt"), "1 2 3").unwrap();
+
+        let steps = plan(&root, day!(1), false, "module".into()).unwrap();
+        let actions: Vec<&Action> = steps.iter().map(|step| &step.action).collect();
+        assert_eq!(
+            actions,
+            [
+                &Action::Create("module".into()),
+                &Action::Keep(FileState::Present),
+                &Action::Create(String::new()),
+            ]
+        );
+
+        execute(&steps, false).unwrap();
+        assert_eq!(
+            fs::read_to_string(root.join("data/inputs/01.txt")).unwrap(),
+            "1 2 3"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("src/bin/01.rs")).unwrap(),
+            "module"
+        );
+        assert!(plan(&root, day!(1), false, "module".into()).is_err());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn overwrite_remembers_the_module() {
+        let root = temp_root("overwrite");
+        fs::write(root.join("src/bin/01.rs"), "old").unwrap();
+
+        let steps = plan(&root, day!(1), true, "new".into()).unwrap();
+        assert_eq!(
+            steps[0].action,
+            Action::Overwrite {
+                contents: "new".into(),
+                previous: b"old".to_vec(),
+            }
+        );
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn rolls_back_on_failure() {
+        let root = temp_root("rollback");
+        fs::write(root.join("src/bin/01.rs"), "old").unwrap();
+        let steps = plan(&root, day!(1), true, "new".into()).unwrap();
+        // the example file appears after planning, so creating it fails.
+        fs::write(root.join("data/examples/01.txt"), "late").unwrap();
+
+        assert!(execute(&steps, false).is_err());
+        assert_eq!(
+            fs::read_to_string(root.join("src/bin/01.rs")).unwrap(),
+            "old"
+        );
+        assert!(!root.join("data/inputs/01.txt").exists());
+        assert_eq!(
+            fs::read_to_string(root.join("data/examples/01.txt")).unwrap(),
+            "late"
+        );
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn dry_run_writes_nothing() {
+        let root = temp_root("dry-run");
+        fs::write(root.join("src/bin/01.rs"), "old").unwrap();
+
+        let steps = plan(&root, day!(1), true, "new".into()).unwrap();
+        let descriptions = execute(&steps, true).unwrap();
+        assert!(descriptions[0].starts_with("Would overwrite module file"));
+        assert_eq!(
+            fs::read_to_string(root.join("src/bin/01.rs")).unwrap(),
+            "old"
+        );
+        assert!(!root.join("data/inputs/01.txt").exists());
+        assert!(!root.join("data/examples/01.txt").exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
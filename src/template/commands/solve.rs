@@ -1,30 +1,116 @@
-use std::process::{Command, Stdio};
+use std::{
+    io::{BufRead, BufReader},
+    process::{self, Command, Stdio},
+};
 
-use crate::template::Day;
+use crate::template::status::{file_state, FileState};
+use crate::template::watch;
+use crate::template::{Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
 
-pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
+/// Options of `cargo solve` that are passed on to every invocation of the solution bin.
+#[derive(Clone)]
+pub struct RunOptions {
+    pub release: bool,
+    pub dhat: bool,
+    /// Run every variant of a part and check that they agree, see `solution!`.
+    pub all_variants: bool,
+    /// Let the solution print diagnostics, see [`crate::template::explain_requested`].
+    pub explain: bool,
+    /// Build with the `visualize` feature and let the solution draw its frames.
+    pub visualize: Option<VisualizeOptions>,
+}
+
+/// Playback and export options of `cargo solve --visualize`, see [`crate::visualize`].
+#[derive(Clone)]
+pub struct VisualizeOptions {
+    pub fps: Option<u32>,
+    pub step: bool,
+    pub export: Option<String>,
+    pub format: Option<String>,
+    pub scale: Option<u32>,
+    pub every: Option<u32>,
+}
+
+/// Options of `cargo solve --watch`.
+#[derive(Clone, Copy)]
+pub struct WatchOptions {
+    /// Run the tests of the day before running it.
+    pub test: bool,
+}
+
+fn cargo_args(day: Day, options: &RunOptions, bin_args: &[String]) -> Vec<String> {
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
 
-    if dhat {
-        cmd_args.extend([
-            "--profile".to_string(),
-            "dhat".to_string(),
-            "--features".to_string(),
-            "dhat-heap".to_string(),
-        ]);
-    } else if release {
+    let mut features = vec![];
+
+    if options.dhat {
+        cmd_args.extend(["--profile".to_string(), "dhat".to_string()]);
+        features.push("dhat-heap");
+    } else if options.release {
         cmd_args.push("--release".to_string());
     }
 
+    if options.visualize.is_some() {
+        features.push("visualize");
+    }
+
+    if !features.is_empty() {
+        cmd_args.push("--features".to_string());
+        cmd_args.push(features.join(","));
+    }
+
     cmd_args.push("--".to_string());
+    cmd_args.extend_from_slice(bin_args);
+
+    if options.all_variants {
+        cmd_args.push("--all-variants".to_string());
+    }
+
+    if options.explain {
+        cmd_args.push("--explain".to_string());
+    }
+
+    if let Some(visualize) = &options.visualize {
+        cmd_args.push("--visualize".to_string());
+        let values = [
+            ("--fps", visualize.fps.map(|v| v.to_string())),
+            ("--export", visualize.export.clone()),
+            ("--format", visualize.format.clone()),
+            ("--scale", visualize.scale.map(|v| v.to_string())),
+            ("--every", visualize.every.map(|v| v.to_string())),
+        ];
+        for (name, value) in values {
+            if let Some(value) = value {
+                cmd_args.push(name.to_string());
+                cmd_args.push(value);
+            }
+        }
+        if visualize.step {
+            cmd_args.push("--step".to_string());
+        }
+    }
+
+    cmd_args
+}
+
+pub fn handle(day: Day, options: RunOptions, submit_part: Option<u8>, watch: Option<WatchOptions>) {
+    if let Some(watch) = watch {
+        if submit_part.is_some() {
+            eprintln!("`--submit` can not be combined with `--watch`.");
+            process::exit(1);
+        }
+        return handle_watch(day, options, watch);
+    }
+
+    let mut bin_args = vec![];
 
     if let Some(submit_part) = submit_part {
-        cmd_args.push("--submit".to_string());
-        cmd_args.push(submit_part.to_string());
+        bin_args.push("--submit".to_string());
+        bin_args.push(submit_part.to_string());
     }
 
     let mut cmd = Command::new("cargo")
-        .args(&cmd_args)
+        .args(cargo_args(day, &options, &bin_args))
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit())
         .spawn()
@@ -32,3 +118,83 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
 
     cmd.wait().unwrap();
 }
+
+/// Re-run a day whenever its bin, the library or its data files change.
+fn handle_watch(day: Day, options: RunOptions, watch_options: WatchOptions) {
+    let mut previous: Option<[Option<String>; 2]> = None;
+    let mut snapshot = watch::snapshot(day);
+
+    loop {
+        watch::clear_screen();
+        println!("{ANSI_ITALIC}Watching day {day} for changes. Press Ctrl-C to stop.{ANSI_RESET}");
+
+        if let Some(answers) = run_once(day, &options, watch_options) {
+            if let Some(previous) = &previous {
+                println!();
+                println!("{ANSI_BOLD}Changes{ANSI_RESET}");
+                for line in watch::diff_answers(previous, &answers) {
+                    println!("{line}");
+                }
+            }
+            previous = Some(answers);
+        }
+
+        snapshot = watch::wait_for_change(day, &snapshot);
+    }
+}
+
+/// Run tests, the example and the real input. Returns the answers for the real input, if it ran.
+fn run_once(
+    day: Day,
+    options: &RunOptions,
+    watch_options: WatchOptions,
+) -> Option<[Option<String>; 2]> {
+    if watch_options.test {
+        println!("\n{ANSI_BOLD}Tests{ANSI_RESET}");
+        let passed = Command::new("cargo")
+            .args(["test", "--quiet", "--bin", &day.to_string()])
+            .status()
+            .is_ok_and(|status| status.success());
+        if !passed {
+            println!("\nTests failed, skipping inputs.");
+            return None;
+        }
+    }
+
+    if file_state(&format!("data/examples/{day}.txt")) == FileState::Present {
+        println!("\n{ANSI_BOLD}Example{ANSI_RESET}");
+        run_captured(day, options, &["--example".into()]);
+    }
+
+    if file_state(&format!("data/inputs/{day}.txt")) == FileState::Missing {
+        println!("\nNo input for day {day}, run `cargo download {day}` first.");
+        return None;
+    }
+
+    println!("\n{ANSI_BOLD}Input{ANSI_RESET}");
+    let output = run_captured(day, options, &[])?;
+    Some(watch::parse_answers(&output))
+}
+
+/// Run the solution bin, forwarding its output while capturing stdout.
+/// Returns `None` if the bin could not be built or run.
+fn run_captured(day: Day, options: &RunOptions, bin_args: &[String]) -> Option<Vec<String>> {
+    let mut args = cargo_args(day, options, bin_args);
+    args.insert(1, "--quiet".into());
+
+    let mut cmd = Command::new("cargo")
+        .args(&args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .ok()?;
+
+    let mut output = vec![];
+    for line in BufReader::new(cmd.stdout.take()?).lines() {
+        let Ok(line) = line else { break };
+        println!("{line}");
+        output.push(line);
+    }
+
+    cmd.wait().ok()?.success().then_some(output)
+}
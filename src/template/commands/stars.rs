@@ -0,0 +1,31 @@
+use crate::template::stats::{self, Stats};
+use crate::template::timings::Timings;
+use crate::template::{all_days, readme_stars};
+
+pub fn handle() {
+    let stars = readme_stars::collect(&Stats::read_from_file(), &Timings::read_from_file());
+
+    let line: String = all_days()
+        .map(|day| match stars.iter().find(|s| s.day == day) {
+            Some(s) if s.part_1 && s.part_2 => '★',
+            Some(_) => '☆',
+            None => '·',
+        })
+        .collect();
+    let total: usize = stars
+        .iter()
+        .map(|s| usize::from(s.part_1) + usize::from(s.part_2))
+        .sum();
+
+    println!("{line}  {total}/50");
+    println!();
+
+    match readme_stars::update(&stars, stats::year()) {
+        Ok(()) => {
+            println!("Stored updated stars.");
+        }
+        Err(_) => {
+            eprintln!("Failed to store updated stars.");
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use crate::template::stats::{self, format_duration, format_timestamp, Stats};
+use crate::template::{readme_stats, ANSI_BOLD, ANSI_RESET};
+
+pub fn handle(store: bool) {
+    let stats = Stats::read_from_file();
+    let year = stats::year();
+
+    if stats.data.is_empty() {
+        println!("No stats recorded yet. Scaffold a day and submit an answer to start tracking.");
+        return;
+    }
+
+    println!(
+        "{ANSI_BOLD}{:<4}{:<19}{:<13}{:<13}{ANSI_RESET}",
+        "Day", "Started (UTC)", "Part 1", "Part 2"
+    );
+
+    for stat in &stats.data {
+        let started = stat
+            .started_at(year)
+            .map_or_else(|| "-".into(), format_timestamp);
+        let part_1 = stat
+            .time_to_solve(1, year)
+            .map_or_else(|| "-".into(), format_duration);
+        let part_2 = stat
+            .time_to_solve(2, year)
+            .map_or_else(|| "-".into(), format_duration);
+
+        println!(
+            "{:<4}{started:<19}{part_1:<13}{part_2:<13}",
+            stat.day.to_string()
+        );
+    }
+
+    if store {
+        println!();
+        match readme_stats::update(&stats, year) {
+            Ok(()) => {
+                println!("Stored updated stats.");
+            }
+            Err(_) => {
+                eprintln!("Failed to store updated stats.");
+            }
+        }
+    }
+}
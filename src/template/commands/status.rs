@@ -0,0 +1,65 @@
+use crate::template::stats::Stats;
+use crate::template::status::{DayStatus, FileState};
+use crate::template::timings::Timings;
+use crate::template::{all_days, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+fn check(value: bool) -> &'static str {
+    if value {
+        "✔"
+    } else {
+        "✖"
+    }
+}
+
+fn file(state: FileState) -> &'static str {
+    match state {
+        FileState::Present => "✔",
+        FileState::Empty => "∅",
+        FileState::Missing => "✖",
+    }
+}
+
+fn pair(values: [bool; 2]) -> String {
+    format!("{} {}", check(values[0]), check(values[1]))
+}
+
+pub fn handle(run_tests: bool) {
+    let stats = Stats::read_from_file();
+    let timings = Timings::read_from_file();
+
+    println!(
+        "{ANSI_BOLD}{:<5}{:<5}{:<7}{:<9}{:<8}{:<7}{:<7}{:<9}{:<9}{ANSI_RESET}",
+        "Day", "Bin", "Input", "Example", "Puzzle", "Parts", "Tests", "Answers", "Timings"
+    );
+
+    for day in all_days() {
+        let status = DayStatus::collect(day, &stats, &timings, run_tests);
+
+        let parts = status
+            .stubs
+            .map_or_else(|| "-".into(), |stubs| pair([!stubs[0], !stubs[1]]));
+        let tests = match status.tests {
+            Some(passed) => check(passed),
+            None => "-",
+        };
+
+        println!(
+            "{:<5}{:<5}{:<7}{:<9}{:<8}{:<7}{:<7}{:<9}{:<9}",
+            status.day.to_string(),
+            check(status.bin),
+            file(status.input),
+            file(status.example),
+            file(status.puzzle),
+            parts,
+            tests,
+            pair(status.answers),
+            pair(status.timings),
+        );
+    }
+
+    println!();
+    println!(
+        "{ANSI_ITALIC}✔ ok, ∅ empty file, ✖ missing / failing. \
+        Parts are ✖ while they still return the scaffolded `None`.{ANSI_RESET}"
+    );
+}
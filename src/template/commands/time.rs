@@ -1,8 +1,9 @@
 use std::collections::HashSet;
 
 use crate::template::run_multi::run_multi;
+use crate::template::stats::{self, Stats};
 use crate::template::timings::Timings;
-use crate::template::{all_days, readme_benchmarks, Day};
+use crate::template::{all_days, readme_benchmarks, readme_stars, status, Day};
 
 pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
     let stored_timings = Timings::read_from_file();
@@ -14,7 +15,7 @@ pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
             } else {
                 // when the `--all` flag is not set, filter out days that are fully benched.
                 all_days()
-                    .filter(|day| !stored_timings.is_day_complete(*day))
+                    .filter(|day| status::needs_benching(&stored_timings, *day))
                     .collect()
             }
         },
@@ -27,6 +28,8 @@ pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
         let merged_timings = stored_timings.merge(&timings);
         merged_timings.store_file().unwrap();
 
+        let stars = readme_stars::collect(&Stats::read_from_file(), &merged_timings);
+
         println!();
         match readme_benchmarks::update(merged_timings) {
             Ok(()) => {
@@ -36,5 +39,14 @@ pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
                 eprintln!("Failed to store updated benchmarks.");
             }
         }
+
+        match readme_stars::update(&stars, stats::year()) {
+            Ok(()) => {
+                println!("Stored updated stars.");
+            }
+            Err(_) => {
+                eprintln!("Failed to store updated stars.");
+            }
+        }
     }
 }
@@ -0,0 +1,282 @@
+/// Private leaderboard model, parsed from the JSON served by the aoc website.
+use std::{collections::HashMap, str::FromStr};
+use tinyjson::JsonValue;
+
+use crate::template::{all_days, Day};
+
+/// A single member of a private leaderboard.
+#[derive(Clone, Debug)]
+pub struct Member {
+    pub id: u64,
+    pub name: String,
+    pub stars: u32,
+    pub local_score: u64,
+    /// Star timestamps (seconds since the unix epoch) per day, for part 1 and part 2.
+    pub completions: HashMap<Day, [Option<u64>; 2]>,
+}
+
+/// A private leaderboard.
+#[derive(Clone, Debug)]
+pub struct Leaderboard {
+    pub event: String,
+    pub members: Vec<Member>,
+}
+
+/// A member's position in the recalculated standings.
+#[derive(Clone, Debug)]
+pub struct Standing<'a> {
+    pub member: &'a Member,
+    pub score: u64,
+}
+
+impl Member {
+    /// Timestamp at which the member earned the star for `part` of `day`.
+    pub fn star_ts(&self, day: Day, part: u8) -> Option<u64> {
+        let index = usize::from(part.checked_sub(1)?);
+        *self.completions.get(&day)?.get(index)?
+    }
+
+    /// Seconds between the first and second star of `day`.
+    pub fn delta(&self, day: Day) -> Option<u64> {
+        Some(self.star_ts(day, 2)?.saturating_sub(self.star_ts(day, 1)?))
+    }
+
+    fn last_star_ts(&self) -> u64 {
+        self.completions
+            .values()
+            .flat_map(|parts| parts.iter().flatten())
+            .copied()
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl Leaderboard {
+    /// Recalculate local scores from star timestamps.
+    /// For every star, the first member to get it receives `N` points, the second `N - 1`, etc.,
+    /// where `N` is the number of members on the leaderboard.
+    pub fn local_scores(&self) -> HashMap<u64, u64> {
+        let n = self.members.len() as u64;
+        let mut scores: HashMap<u64, u64> = self.members.iter().map(|m| (m.id, 0)).collect();
+
+        for day in all_days() {
+            for part in 1..=2 {
+                let mut finishers: Vec<(u64, u64)> = self
+                    .members
+                    .iter()
+                    .filter_map(|m| m.star_ts(day, part).map(|ts| (ts, m.id)))
+                    .collect();
+                finishers.sort_unstable();
+
+                for (rank, (_, id)) in finishers.iter().enumerate() {
+                    *scores.entry(*id).or_default() += n - rank as u64;
+                }
+            }
+        }
+
+        scores
+    }
+
+    /// Members ordered by recalculated local score, ties broken by who got their last star first.
+    pub fn standings(&self) -> Vec<Standing<'_>> {
+        let scores = self.local_scores();
+        let mut standings: Vec<Standing> = self
+            .members
+            .iter()
+            .map(|member| Standing {
+                member,
+                score: scores.get(&member.id).copied().unwrap_or(0),
+            })
+            .collect();
+
+        standings.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then(b.member.stars.cmp(&a.member.stars))
+                .then(a.member.last_star_ts().cmp(&b.member.last_star_ts()))
+                .then(a.member.id.cmp(&b.member.id))
+        });
+
+        standings
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn get_u64(json: &HashMap<String, JsonValue>, key: &str) -> Option<u64> {
+    json.get(key)
+        .and_then(|v| v.get::<f64>())
+        .map(|x| *x as u64)
+}
+
+impl TryFrom<&str> for Leaderboard {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(value).or(Err("not valid JSON file."))?;
+
+        let json = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?;
+
+        let event = json
+            .get("event")
+            .and_then(|v| v.get::<String>())
+            .cloned()
+            .ok_or("expected JSON document to have a string `event`.")?;
+
+        let mut members = json
+            .get("members")
+            .and_then(|v| v.get::<HashMap<String, JsonValue>>())
+            .ok_or("expected `json.members` to be an object.")?
+            .values()
+            .map(Member::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        members.sort_unstable_by_key(|m| m.id);
+
+        Ok(Leaderboard { event, members })
+    }
+}
+
+impl TryFrom<&JsonValue> for Member {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected member to be a JSON object.")?;
+
+        let id = get_u64(json, "id").ok_or("Expected member.id to be a number.")?;
+
+        // anonymous users have a `null` name.
+        let name = json
+            .get("name")
+            .and_then(|v| v.get::<String>())
+            .cloned()
+            .unwrap_or_else(|| format!("(anonymous user #{id})"));
+
+        let stars = get_u64(json, "stars")
+            .and_then(|x| u32::try_from(x).ok())
+            .ok_or("Expected member.stars to be a number.")?;
+        let local_score =
+            get_u64(json, "local_score").ok_or("Expected member.local_score to be a number.")?;
+
+        let mut completions = HashMap::new();
+
+        let days = json
+            .get("completion_day_level")
+            .and_then(|v| v.get::<HashMap<String, JsonValue>>())
+            .ok_or("Expected member.completion_day_level to be an object.")?;
+
+        for (day, parts) in days {
+            let day = Day::from_str(day).map_err(|e| e.to_string())?;
+            let parts = parts
+                .get::<HashMap<String, JsonValue>>()
+                .ok_or("Expected completion_day_level entries to be objects.")?;
+
+            let mut timestamps = [None, None];
+            for (part, star) in parts {
+                let index = match part.as_str() {
+                    "1" => 0,
+                    "2" => 1,
+                    _ => return Err(format!("Unexpected part `{part}` in completion_day_level.")),
+                };
+                let star = star
+                    .get::<HashMap<String, JsonValue>>()
+                    .ok_or("Expected star to be a JSON object.")?;
+                timestamps[index] = Some(
+                    get_u64(star, "get_star_ts")
+                        .ok_or("Expected star.get_star_ts to be a number.")?,
+                );
+            }
+            completions.insert(day, timestamps);
+        }
+
+        Ok(Member {
+            id,
+            name,
+            stars,
+            local_score,
+            completions,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Leaderboard;
+    use crate::day;
+
+    fn get_mock_leaderboard() -> Leaderboard {
+        let json = r#"{
+            "event": "2024",
+            "owner_id": 1,
+            "members": {
+                "1": {
+                    "id": 1, "name": "alice", "stars": 3, "local_score": 8, "global_score": 0,
+                    "last_star_ts": 1733116000,
+                    "completion_day_level": {
+                        "1": { "1": { "get_star_ts": 1733029500, "star_index": 0 },
+                               "2": { "get_star_ts": 1733029900, "star_index": 1 } },
+                        "2": { "1": { "get_star_ts": 1733116000, "star_index": 2 } }
+                    }
+                },
+                "2": {
+                    "id": 2, "name": null, "stars": 2, "local_score": 5, "global_score": 0,
+                    "last_star_ts": 1733029800,
+                    "completion_day_level": {
+                        "1": { "1": { "get_star_ts": 1733029300, "star_index": 0 },
+                               "2": { "get_star_ts": 1733029800, "star_index": 1 } }
+                    }
+                },
+                "3": {
+                    "id": 3, "name": "carol", "stars": 0, "local_score": 0, "global_score": 0,
+                    "last_star_ts": 0, "completion_day_level": {}
+                }
+            }
+        }"#;
+        Leaderboard::try_from(json).unwrap()
+    }
+
+    #[test]
+    fn parses_leaderboard() {
+        let leaderboard = get_mock_leaderboard();
+        assert_eq!(leaderboard.event, "2024");
+        assert_eq!(leaderboard.members.len(), 3);
+        assert_eq!(leaderboard.members[1].name, "(anonymous user #2)");
+        assert_eq!(leaderboard.members[0].star_ts(day!(2), 1), Some(1733116000));
+        assert_eq!(leaderboard.members[0].star_ts(day!(2), 2), None);
+    }
+
+    #[test]
+    fn computes_deltas() {
+        let leaderboard = get_mock_leaderboard();
+        assert_eq!(leaderboard.members[0].delta(day!(1)), Some(400));
+        assert_eq!(leaderboard.members[0].delta(day!(2)), None);
+    }
+
+    #[test]
+    fn recalculates_local_scores() {
+        let leaderboard = get_mock_leaderboard();
+        let scores = leaderboard.local_scores();
+        // day 1: member 2 first on both parts (3 + 3), alice second (2 + 2), alice alone on day 2 (3).
+        assert_eq!(scores[&1], 7);
+        assert_eq!(scores[&2], 6);
+        assert_eq!(scores[&3], 0);
+
+        let standings = leaderboard.standings();
+        assert_eq!(standings[0].member.id, 1);
+        assert_eq!(standings[1].member.id, 2);
+        assert_eq!(standings[2].member.id, 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_malformed_leaderboard() {
+        Leaderboard::try_from(r#"{ "event": "2024", "members": { "1": { "id": 1 } } }"#).unwrap();
+    }
+}
@@ -2,14 +2,24 @@ use std::{env, fs};
 
 pub mod aoc_cli;
 pub mod commands;
+pub mod registry;
 pub mod runner;
 
 pub use day::*;
+pub use solution::Solution;
 
 mod day;
+mod leaderboard;
 mod readme_benchmarks;
+mod readme_stars;
+mod readme_stats;
 mod run_multi;
+mod solution;
+mod stats;
+mod status;
+pub mod templates;
 mod timings;
+mod watch;
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
@@ -36,9 +46,23 @@ pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
     f.expect("could not open input file")
 }
 
-/// Creates the constant `DAY` and sets up the input and runner for each part.
+/// Whether the solution should print diagnostics about its input, see `cargo solve --explain`.
+/// Timed runs never explain, printing would distort the measurements.
+pub fn explain_requested() -> bool {
+    env::args().any(|arg| arg == "--explain") && !env::args().any(|arg| arg == "--time")
+}
+
+/// Creates the constants `DAY` and `SOLUTION` and sets up the input and runner for each part.
 ///
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+/// Alternatively, it names a type implementing [`Solution`], e.g. `solution!(5, Day05)`.
+///
+/// Parts with several implementations list them as named variants, the first one is the primary:
+/// `solution!(7, [bitmask: part_one, dfs: part_one_dfs], [dfs: part_two])`.
+/// `cargo solve <day> --all-variants` runs all of them and checks that they agree.
+///
+/// `SOLUTION` registers the day with the in-process [`registry`], which is how the `advent`
+/// binary runs all days without spawning a process per day.
 #[macro_export]
 macro_rules! solution {
     ($day:expr) => {
@@ -51,18 +75,52 @@ macro_rules! solution {
         $crate::solution!(@impl $day, [part_two, 2]);
     };
 
+    ($day:expr, [$($name_1:ident : $func_1:path),+ $(,)?], [$($name_2:ident : $func_2:path),+ $(,)?]) => {
+        $crate::solution!(@run $day, |input| {
+            use $crate::template::runner::*;
+            run_variants(&[$((stringify!($name_1), $func_1 as fn(&str) -> Option<_>)),+], input, DAY, 1);
+            run_variants(&[$((stringify!($name_2), $func_2 as fn(&str) -> Option<_>)),+], input, DAY, 2);
+        });
+    };
+
+    ($day:expr, $solution:ty) => {
+        $crate::solution!(@run $day, |input| {
+            $crate::template::runner::run_solution::<$solution>(input, DAY);
+        });
+    };
+
     (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
+        $crate::solution!(@run $day, |input| {
+            use $crate::template::runner::*;
+            $( run_part($func, input, DAY, $part); )*
+        });
+    };
+
+    (@run $day:expr, $run:expr) => {
         /// The current day.
         const DAY: $crate::template::Day = $crate::day!($day);
 
+        /// The solution of the current day, as registered with the in-process registry.
+        #[allow(dead_code)]
+        pub const SOLUTION: $crate::template::registry::Entry =
+            $crate::template::registry::Entry {
+                day: DAY,
+                run: $run,
+            };
+
         #[cfg(feature = "dhat-heap")]
         #[global_allocator]
         static ALLOC: dhat::Alloc = dhat::Alloc;
 
         fn main() {
-            use $crate::template::runner::*;
-            let input = $crate::template::read_file("inputs", DAY);
-            $( run_part($func, &input, DAY, $part); )*
+            // `--example` runs the solution against the example input instead.
+            let folder = if std::env::args().any(|arg| arg == "--example") {
+                "examples"
+            } else {
+                "inputs"
+            };
+            let input = $crate::template::read_file(folder, DAY);
+            (SOLUTION.run)(&input);
         }
     };
 }
@@ -0,0 +1,187 @@
+/// Module that updates the readme with a table of collected stars.
+/// Renders the same table the `advent-readme-stars` GitHub action does, but works offline.
+use std::fs;
+
+use crate::template::readme_benchmarks::{replace_table, Error};
+use crate::template::stats::Stats;
+use crate::template::timings::Timings;
+use crate::template::{all_days, Day};
+
+static MARKER: &str = "<!--- advent_readme_stars table --->";
+
+/// Collected stars for a single day.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DayStars {
+    pub day: Day,
+    pub part_1: bool,
+    pub part_2: bool,
+}
+
+/// Derive stars from accepted answers. Days that have been benched completely count as solved.
+pub fn collect(stats: &Stats, timings: &Timings) -> Vec<DayStars> {
+    all_days()
+        .map(|day| {
+            let complete = timings.is_day_complete(day);
+            DayStars {
+                day,
+                part_1: complete || stats.is_part_accepted(day, 1),
+                part_2: complete || stats.is_part_accepted(day, 2),
+            }
+        })
+        .filter(|s| s.part_1 || s.part_2)
+        .collect()
+}
+
+fn star(collected: bool) -> &'static str {
+    if collected {
+        "⭐"
+    } else {
+        " "
+    }
+}
+
+fn construct_table(prefix: &str, stars: &[DayStars], year: Option<u16>) -> String {
+    let header = match year {
+        Some(year) => format!("{prefix} {year} Results"),
+        None => format!("{prefix} Results"),
+    };
+
+    let mut lines: Vec<String> = vec![
+        MARKER.into(),
+        header,
+        String::new(),
+        "| Day | Part 1 | Part 2 |".into(),
+        "| :---: | :---: | :---: |".into(),
+    ];
+
+    for s in stars {
+        let day = s.day.into_inner();
+        let label = match year {
+            Some(year) => format!("[Day {day}](https://adventofcode.com/{year}/day/{day})"),
+            None => format!("Day {day}"),
+        };
+        lines.push(format!(
+            "| {label} | {} | {} |",
+            star(s.part_1),
+            star(s.part_2)
+        ));
+    }
+
+    let total = stars
+        .iter()
+        .map(|s| usize::from(s.part_1) + usize::from(s.part_2))
+        .sum::<usize>();
+
+    lines.push(String::new());
+    lines.push(format!("**Progress: {total}/50 ⭐**"));
+    lines.push(MARKER.into());
+
+    lines.join("\n")
+}
+
+fn update_content(s: &mut String, stars: &[DayStars], year: Option<u16>) -> Result<(), Error> {
+    let table = construct_table("##", stars, year);
+    replace_table(s, MARKER, &table)
+}
+
+pub fn update(stars: &[DayStars], year: Option<u16>) -> Result<(), Error> {
+    let path = "README.md";
+    let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
+    update_content(&mut readme, stars, year)?;
+    fs::write(path, &readme)?;
+    Ok(())
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{collect, update_content, DayStars, MARKER};
+    use crate::{
+        day,
+        template::stats::{Stat, Stats},
+        template::timings::{Timing, Timings},
+    };
+
+    #[test]
+    fn collects_stars_from_stats_and_timings() {
+        let stats = Stats {
+            data: vec![Stat {
+                day: day!(3),
+                scaffolded_at: None,
+                part_1_at: Some(1),
+                part_2_at: None,
+            }],
+        };
+        let timings = Timings {
+            data: vec![
+                Timing {
+                    day: day!(1),
+                    parse: None,
+                    part_1: Some("1ms".into()),
+                    part_2: Some("2ms".into()),
+                    total_nanos: 3_000_000_f64,
+                    variants: vec![],
+                },
+                Timing {
+                    day: day!(2),
+                    parse: None,
+                    part_1: Some("1ms".into()),
+                    part_2: None,
+                    total_nanos: 1_000_000_f64,
+                    variants: vec![],
+                },
+            ],
+        };
+
+        let stars = collect(&stats, &timings);
+        assert_eq!(
+            stars,
+            vec![
+                DayStars {
+                    day: day!(1),
+                    part_1: true,
+                    part_2: true
+                },
+                DayStars {
+                    day: day!(3),
+                    part_1: true,
+                    part_2: false
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn format_stars() {
+        let stars = [
+            DayStars {
+                day: day!(1),
+                part_1: true,
+                part_2: true,
+            },
+            DayStars {
+                day: day!(2),
+                part_1: true,
+                part_2: false,
+            },
+        ];
+        let mut s = format!("foo\n{}\nbaz", MARKER);
+        update_content(&mut s, &stars, Some(2024)).unwrap();
+        update_content(&mut s, &stars, Some(2024)).unwrap();
+        let expected = [
+            "foo",
+            "<!--- advent_readme_stars table --->",
+            "## 2024 Results",
+            "",
+            "| Day | Part 1 | Part 2 |",
+            "| :---: | :---: | :---: |",
+            "| [Day 1](https://adventofcode.com/2024/day/1) | ⭐ | ⭐ |",
+            "| [Day 2](https://adventofcode.com/2024/day/2) | ⭐ |   |",
+            "",
+            "**Progress: 3/50 ⭐**",
+            "<!--- advent_readme_stars table --->",
+            "baz",
+        ]
+        .join("\n");
+        assert_eq!(s, expected);
+    }
+}
@@ -0,0 +1,109 @@
+/// Module that updates the readme with personal solve times.
+/// Shares the marker handling with the benchmarking table.
+use std::fs;
+
+use crate::template::readme_benchmarks::{get_path_for_bin, replace_table, Error};
+use crate::template::stats::{format_duration, format_timestamp, Stats};
+
+static MARKER: &str = "<!--- stats table --->";
+
+fn format_part(stats_time: Option<u64>) -> String {
+    stats_time.map_or_else(|| "-".into(), |t| format!("`{}`", format_duration(t)))
+}
+
+fn construct_table(prefix: &str, stats: &Stats, year: Option<u16>) -> String {
+    let header = format!("{prefix} Solve Times");
+
+    let mut lines: Vec<String> = vec![
+        MARKER.into(),
+        header,
+        String::new(),
+        "| Day | Started | Part 1 | Part 2 |".into(),
+        "| :---: | :---: | :---: | :---:  |".into(),
+    ];
+
+    for stat in &stats.data {
+        let path = get_path_for_bin(stat.day);
+        lines.push(format!(
+            "| [Day {}]({}) | {} | {} | {} |",
+            stat.day.into_inner(),
+            path,
+            stat.started_at(year)
+                .map_or_else(|| "-".into(), format_timestamp),
+            format_part(stat.time_to_solve(1, year)),
+            format_part(stat.time_to_solve(2, year)),
+        ));
+    }
+
+    lines.push(MARKER.into());
+
+    lines.join("\n")
+}
+
+fn update_content(s: &mut String, stats: &Stats, year: Option<u16>) -> Result<(), Error> {
+    let table = construct_table("##", stats, year);
+    replace_table(s, MARKER, &table)
+}
+
+pub fn update(stats: &Stats, year: Option<u16>) -> Result<(), Error> {
+    let path = "README.md";
+    let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
+    update_content(&mut readme, stats, year)?;
+    fs::write(path, &readme)?;
+    Ok(())
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{update_content, MARKER};
+    use crate::{
+        day,
+        template::stats::{Stat, Stats},
+    };
+
+    fn get_mock_stats() -> Stats {
+        Stats {
+            data: vec![
+                Stat {
+                    day: day!(1),
+                    scaffolded_at: Some(1_733_029_000),
+                    part_1_at: Some(1_733_029_500),
+                    part_2_at: Some(1_733_030_000),
+                },
+                Stat {
+                    day: day!(2),
+                    scaffolded_at: None,
+                    part_1_at: None,
+                    part_2_at: None,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn errors_if_marker_not_present() {
+        let mut s = "# readme".to_string();
+        update_content(&mut s, &get_mock_stats(), Some(2024)).unwrap();
+    }
+
+    #[test]
+    fn format_stats() {
+        let mut s = format!("foo\n{}\nbaz", MARKER);
+        update_content(&mut s, &get_mock_stats(), Some(2024)).unwrap();
+        let expected = [
+            "foo",
+            "<!--- stats table --->",
+            "## Solve Times",
+            "",
+            "| Day | Started | Part 1 | Part 2 |",
+            "| :---: | :---: | :---: | :---:  |",
+            "| [Day 1](./src/bin/01.rs) | 2024-12-01 05:00 | `00:05:00` | `00:13:20` |",
+            "| [Day 2](./src/bin/02.rs) | 2024-12-02 05:00 | - | - |",
+            "<!--- stats table --->",
+            "baz",
+        ]
+        .join("\n");
+        assert_eq!(s, expected);
+    }
+}
@@ -0,0 +1,77 @@
+/// In-process registry of solutions.
+/// Every day registers an [`Entry`] through the `solution!` macro. The `advent` binary
+/// includes all scaffolded days as modules and runs them in a single process.
+use std::{
+    env, fs,
+    panic::{self, AssertUnwindSafe},
+};
+
+use crate::template::{Day, ANSI_BOLD, ANSI_RESET};
+
+/// A registered solution for one day.
+#[derive(Clone, Copy)]
+pub struct Entry {
+    pub day: Day,
+    /// Runs all parts of the solution against the given input, printing results like `cargo solve`.
+    pub run: fn(&str),
+}
+
+/// Header printed before the output of each day.
+/// `run_multi` relies on it to split the output of the `advent` binary by day.
+pub fn day_header(day: Day) -> String {
+    format!("{ANSI_BOLD}Day {day}{ANSI_RESET}")
+}
+
+/// Run the registered solutions for `days`, in order.
+/// Days without a registered solution are reported as not solved.
+pub fn run(solutions: &[Entry], days: &[Day]) {
+    for (i, day) in days.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+
+        println!("{}", day_header(*day));
+        println!("------");
+
+        let Some(solution) = solutions.iter().find(|s| s.day == *day) else {
+            println!("Not solved.");
+            continue;
+        };
+
+        let Ok(input) = fs::read_to_string(format!("data/inputs/{day}.txt")) else {
+            eprintln!("could not open input file");
+            continue;
+        };
+
+        // a panicking solution should not take down the remaining days.
+        if panic::catch_unwind(AssertUnwindSafe(|| (solution.run)(&input))).is_err() {
+            println!();
+            eprintln!("Day {day} panicked.");
+        }
+    }
+}
+
+/// Entry point of the `advent` binary: `advent [--time] [<day>...]`.
+/// Runs the given days, or every registered day if none are given.
+pub fn main(solutions: &[Entry]) {
+    let mut days: Vec<Day> = env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with("--"))
+        .filter_map(|arg| match arg.parse() {
+            Ok(day) => Some(day),
+            Err(e) => {
+                eprintln!("Ignoring argument \"{arg}\": {e}");
+                None
+            }
+        })
+        .collect();
+
+    if days.is_empty() {
+        days = solutions.iter().map(|s| s.day).collect();
+    }
+
+    days.sort_unstable();
+    days.dedup();
+
+    run(solutions, &days);
+}
@@ -4,35 +4,21 @@ use crate::template::{Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
 
 use super::{
     all_days,
-    timings::{Timing, Timings},
+    timings::{Timing, Timings, VariantTiming},
 };
 
 pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -> Option<Timings> {
-    let mut timings: Vec<Timing> = Vec::with_capacity(days_to_run.len());
+    // NOTE: use non-duplicate, sorted day values.
+    let days: Vec<Day> = all_days().filter(|day| days_to_run.contains(day)).collect();
 
-    let mut need_space = false;
+    let output = child_commands::run_solutions(&days, is_timed, is_release).unwrap();
 
-    // NOTE: use non-duplicate, sorted day values.
-    all_days()
-        .filter(|day| days_to_run.contains(day))
-        .for_each(|day| {
-            if need_space {
-                println!();
-            }
-            need_space = true;
-
-            println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
-            println!("------");
-
-            let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
-
-            if output.is_empty() {
-                println!("Not solved.");
-            } else {
-                let val = child_commands::parse_exec_time(&output, day);
-                timings.push(val);
-            }
-        });
+    // only days that printed results have been solved.
+    let timings: Vec<Timing> = child_commands::split_by_day(&output, &days)
+        .into_iter()
+        .filter(|(_, lines)| lines.iter().any(|l| l.starts_with("Part ")))
+        .map(|(day, lines)| child_commands::parse_exec_time(lines, day))
+        .collect();
 
     if is_timed {
         let timings = Timings { data: timings };
@@ -64,38 +50,48 @@ pub fn get_path_for_bin(day: Day) -> String {
     format!("./src/bin/{day}.rs")
 }
 
-/// All solutions live in isolated binaries.
-/// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
+/// All solutions are compiled into the `advent` binary, see [`crate::template::registry`].
+/// This module encapsulates interaction with this binary, both invoking it as well as parsing the timing output.
 pub mod child_commands {
-    use super::{get_path_for_bin, Error};
-    use crate::template::Day;
+    use super::Error;
+    use crate::template::{registry, Day};
     use std::{
         io::{BufRead, BufReader},
-        path::Path,
         process::{Command, Stdio},
         thread,
     };
 
-    /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
-        // skip command invocation for days that have not been scaffolded yet.
-        if !Path::new(&get_path_for_bin(day)).exists() {
+    /// Run the solutions for the given days in a single invocation of the `advent` bin.
+    pub fn run_solutions(
+        days: &[Day],
+        is_timed: bool,
+        is_release: bool,
+    ) -> Result<Vec<String>, Error> {
+        if days.is_empty() {
             return Ok(vec![]);
         }
 
-        let day_padded = day.to_string();
-        let mut args = vec!["run", "--quiet", "--bin", &day_padded];
+        let mut args: Vec<String> = vec![
+            "run".into(),
+            "--quiet".into(),
+            "--bin".into(),
+            "advent".into(),
+        ];
 
         if is_release {
-            args.push("--release");
+            args.push("--release".into());
         }
 
+        args.push("--".into());
+
         if is_timed {
-            // mirror `--time` flag to child invocations.
-            args.push("--");
-            args.push("--time");
+            // mirror `--time` flag to the child invocation and bench every variant of a part.
+            args.push("--time".into());
+            args.push("--all-variants".into());
         }
 
+        args.extend(days.iter().map(Day::to_string));
+
         // spawn child command with piped stdout/stderr.
         // forward output to stdout/stderr while grabbing stdout lines.
 
@@ -128,12 +124,38 @@ pub mod child_commands {
         Ok(output)
     }
 
+    /// Split the output of the `advent` bin into the sections printed for each day.
+    pub fn split_by_day<'a>(output: &'a [String], days: &[Day]) -> Vec<(Day, &'a [String])> {
+        let starts: Vec<(Day, usize)> = output
+            .iter()
+            .enumerate()
+            .filter_map(|(i, line)| {
+                let day = days.iter().find(|d| *line == registry::day_header(**d))?;
+                Some((*day, i + 1))
+            })
+            .collect();
+
+        starts
+            .iter()
+            .enumerate()
+            .map(|(i, (day, start))| {
+                let end = starts.get(i + 1).map_or(output.len(), |(_, next)| next - 1);
+                (*day, &output[*start..end])
+            })
+            .collect()
+    }
+
+    /// Collect the timings of a day. With several variants per part, each variant is printed as
+    /// `Part 1 [name]: ...`; the first variant of a part is the one that counts towards the total.
+    /// The `Parse` line of days implementing [`crate::template::Solution`] counts as well.
     pub fn parse_exec_time(output: &[String], day: Day) -> super::Timing {
         let mut timings = super::Timing {
             day,
+            parse: None,
             part_1: None,
             part_2: None,
             total_nanos: 0_f64,
+            variants: vec![],
         };
 
         output
@@ -148,16 +170,41 @@ pub mod child_commands {
                     return None;
                 };
 
-                let part = l.split(':').next()?;
-                Some((part, timing_str, nanos))
+                let label = l.split(':').next()?;
+                Some((label, timing_str, nanos))
             })
-            .for_each(|(part, timing_str, nanos)| {
-                if part.contains("Part 1") {
-                    timings.part_1 = Some(timing_str.into());
+            .for_each(|(label, timing_str, nanos)| {
+                if label == "Parse" {
+                    timings.parse = Some(timing_str.into());
+                    timings.total_nanos += nanos;
+                    return;
+                }
+
+                let (part, variant) = match label.split_once(" [") {
+                    Some((part, name)) => (part, name.strip_suffix(']')),
+                    None => (label, None),
+                };
+
+                let (number, slot) = if part.contains("Part 1") {
+                    (1, &mut timings.part_1)
                 } else if part.contains("Part 2") {
-                    timings.part_2 = Some(timing_str.into());
+                    (2, &mut timings.part_2)
+                } else {
+                    return;
+                };
+
+                if let Some(name) = variant {
+                    timings.variants.push(super::VariantTiming {
+                        part: number,
+                        name: name.into(),
+                        time: timing_str.into(),
+                    });
+                    if slot.is_some() {
+                        return;
+                    }
                 }
 
+                *slot = Some(timing_str.into());
                 timings.total_nanos += nanos;
             });
 
@@ -174,7 +221,7 @@ pub mod child_commands {
             .split(" samples)")
             .next()?
             .split('(')
-            .last()?
+            .next_back()?
             .split('@')
             .next()?
             .trim();
@@ -205,9 +252,28 @@ pub mod child_commands {
 
     #[cfg(feature = "test_lib")]
     mod tests {
-        use super::parse_exec_time;
+        use super::{parse_exec_time, split_by_day};
+
+        use crate::{day, template::registry::day_header};
 
-        use crate::day;
+        #[test]
+        fn splits_output_by_day() {
+            let output: Vec<String> = vec![
+                day_header(day!(1)),
+                "------".into(),
+                "Part 1: 1".into(),
+                "".into(),
+                day_header(day!(3)),
+                "------".into(),
+                "Not solved.".into(),
+            ];
+            let sections = split_by_day(&output, &[day!(1), day!(3)]);
+            assert_eq!(sections.len(), 2);
+            assert_eq!(sections[0].0, day!(1));
+            assert_eq!(sections[0].1, &output[1..4]);
+            assert_eq!(sections[1].0, day!(3));
+            assert_eq!(sections[1].1, &output[5..]);
+        }
 
         #[test]
         fn parses_execution_times() {
@@ -239,6 +305,48 @@ pub mod child_commands {
             assert_eq!(res.part_2.unwrap(), "100ms");
         }
 
+        #[test]
+        fn parses_parse_times() {
+            let res = parse_exec_time(
+                &[
+                    "Parse: ✓ (3ms @ 10 samples)".into(),
+                    "Part 1: 143 (1ms @ 10 samples)".into(),
+                    "Part 2: 123 (2ms @ 10 samples)".into(),
+                    "".into(),
+                ],
+                day!(5),
+            );
+            assert_approx_eq!(res.total_nanos, 6000000_f64);
+            assert_eq!(res.parse.unwrap(), "3ms");
+            assert_eq!(res.part_1.unwrap(), "1ms");
+            assert_eq!(res.part_2.unwrap(), "2ms");
+        }
+
+        #[test]
+        fn parses_variants() {
+            let res = parse_exec_time(
+                &[
+                    "Part 1 [bitmask]: 3749 (2ms @ 10 samples)".into(),
+                    "Part 1 [dfs]: 3749 (1ms @ 10 samples)".into(),
+                    "Part 2 [dfs]: 11387 (3ms @ 10 samples)".into(),
+                    "".into(),
+                ],
+                day!(7),
+            );
+            assert_approx_eq!(res.total_nanos, 5000000_f64);
+            assert_eq!(res.part_1.unwrap(), "2ms");
+            assert_eq!(res.part_2.unwrap(), "3ms");
+            let variants: Vec<_> = res
+                .variants
+                .iter()
+                .map(|v| (v.part, v.name.as_str(), v.time.as_str()))
+                .collect();
+            assert_eq!(
+                variants,
+                [(1, "bitmask", "2ms"), (1, "dfs", "1ms"), (2, "dfs", "3ms")]
+            );
+        }
+
         #[test]
         fn parses_missing_parts() {
             let res = parse_exec_time(
@@ -7,18 +7,133 @@ use std::time::{Duration, Instant};
 use std::{cmp, env, process};
 
 use crate::template::ANSI_BOLD;
-use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{aoc_cli, stats, Day, Solution, ANSI_ITALIC, ANSI_RESET};
 
 pub fn run_part<I: Copy, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
-    let part_str = format!("Part {part}");
+    if let Some(result) = run_labelled(func, input, &format!("Part {part}")) {
+        submit_and_record(result, day, part);
+    }
+}
 
+/// A named implementation of a part, as registered with `solution!`.
+pub type Variant<T> = (&'static str, fn(&str) -> Option<T>);
+
+/// Run a part that has several named implementations, the first one is the primary variant.
+///
+/// Without `--all-variants`, only the primary variant runs, exactly like [`run_part`].
+/// With it, every variant is run (and benched with `--time`) and printed as `Part 1 [name]: ...`.
+/// Panics if the variants do not agree on the answer.
+pub fn run_variants<T: Display + PartialEq>(
+    variants: &[Variant<T>],
+    input: &str,
+    day: Day,
+    part: u8,
+) {
+    let Some(((_, primary), others)) = variants.split_first() else {
+        return;
+    };
+
+    if !env::args().any(|x| x == "--all-variants") {
+        run_part(primary, input, day, part);
+        return;
+    }
+
+    let results: Vec<Option<T>> = variants
+        .iter()
+        .map(|(name, func)| run_labelled(func, input, &format!("Part {part} [{name}]")))
+        .collect();
+
+    if results.iter().any(|result| *result != results[0]) {
+        let answers: Vec<String> = variants
+            .iter()
+            .zip(&results)
+            .map(|((name, _), result)| match result {
+                Some(result) => format!("{name} = {result}"),
+                None => format!("{name} = ✖"),
+            })
+            .collect();
+        panic!("Variants of part {part} disagree: {}", answers.join(", "));
+    }
+
+    if !others.is_empty() {
+        println!(
+            "{ANSI_ITALIC}{} variants agree.{ANSI_RESET}",
+            variants.len()
+        );
+    }
+
+    if let Some(result) = results.into_iter().next().flatten() {
+        submit_and_record(result, day, part);
+    }
+}
+
+/// Run, time and print a single part under the given label.
+fn run_labelled<I: Copy, T: Display>(
+    func: impl Fn(I) -> Option<T>,
+    input: I,
+    label: &str,
+) -> Option<T> {
     let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+        run_timed(func, input, |result| print_result(result, label, ""));
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    print_result(&result, label, &format_duration(&duration, samples));
 
-    if let Some(result) = result {
-        submit_result(result, day, part);
+    result
+}
+
+/// Run a day implemented via the [`Solution`] trait.
+/// The input is parsed once, errors are reported once per part instead of once per bench sample.
+pub fn run_solution<S: Solution>(input: &str, day: Day) {
+    let (parsed, duration, samples) = run_timed(S::parse, input, |_| print!("Parse: …"));
+
+    let parsed = match parsed {
+        Ok(parsed) => {
+            print!("\r");
+            println!("Parse: ✓{}", format_duration(&duration, samples));
+            parsed
+        }
+        Err(e) => {
+            print!("\r");
+            println!("Parse: ✖             ");
+            eprintln!("Failed to parse input: {e}");
+            return;
+        }
+    };
+
+    run_fallible_part(S::part_one, &parsed, day, 1);
+    run_fallible_part(S::part_two, &parsed, day, 2);
+}
+
+fn run_fallible_part<I: Copy, T: Display, E: Display>(
+    func: impl Fn(I) -> Result<T, E>,
+    input: I,
+    day: Day,
+    part: u8,
+) {
+    let part_str = format!("Part {part}");
+
+    let (result, duration, samples) = run_timed(func, input, |result| {
+        print_result(&result.as_ref().ok(), &part_str, "");
+    });
+
+    print_result(
+        &result.as_ref().ok(),
+        &part_str,
+        &format_duration(&duration, samples),
+    );
+
+    match result {
+        Ok(result) => submit_and_record(result, day, part),
+        Err(e) => eprintln!("{part_str} failed: {e}"),
+    }
+}
+
+/// Submit a result if requested and record the time it was accepted.
+fn submit_and_record<T: Display>(result: T, day: Day, part: u8) {
+    if let Some(Ok(output)) = submit_result(result, day, part) {
+        if aoc_cli::is_accepted(&output) {
+            stats::record(|stats, now| stats.record_accepted(day, part, now));
+        }
     }
 }
 
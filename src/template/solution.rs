@@ -0,0 +1,76 @@
+/// Trait-based alternative to the free `part_one` / `part_two` functions of a day.
+/// The input is parsed once and shared by both parts, which may return different types.
+use std::fmt::Display;
+
+/// A solution for one day, registered with `solution!(<day>, <type>)`.
+///
+/// ```ignore
+/// struct Day05;
+///
+/// impl Solution for Day05 {
+///     type Input = Vec<u32>;
+///     type Output1 = u32;
+///     type Output2 = String;
+///     type Error = anyhow::Error;
+///
+///     fn parse(input: &str) -> Result<Self::Input, Self::Error> { ... }
+///     fn part_one(input: &Self::Input) -> Result<Self::Output1, Self::Error> { ... }
+///     fn part_two(input: &Self::Input) -> Result<Self::Output2, Self::Error> { ... }
+/// }
+/// ```
+pub trait Solution {
+    /// The parsed puzzle input, shared by both parts.
+    type Input;
+    type Output1: Display;
+    type Output2: Display;
+    type Error: Display;
+
+    fn parse(input: &str) -> Result<Self::Input, Self::Error>;
+
+    fn part_one(input: &Self::Input) -> Result<Self::Output1, Self::Error>;
+
+    fn part_two(input: &Self::Input) -> Result<Self::Output2, Self::Error>;
+
+    /// Parse `input` and solve part one.
+    fn solve_part_one(input: &str) -> Result<Self::Output1, Self::Error> {
+        Self::part_one(&Self::parse(input)?)
+    }
+
+    /// Parse `input` and solve part two.
+    fn solve_part_two(input: &str) -> Result<Self::Output2, Self::Error> {
+        Self::part_two(&Self::parse(input)?)
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Solution;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Input = Vec<u32>;
+        type Output1 = u32;
+        type Output2 = String;
+        type Error = std::num::ParseIntError;
+
+        fn parse(input: &str) -> Result<Self::Input, Self::Error> {
+            input.split(',').map(str::parse).collect()
+        }
+
+        fn part_one(input: &Self::Input) -> Result<Self::Output1, Self::Error> {
+            Ok(input.iter().sum())
+        }
+
+        fn part_two(input: &Self::Input) -> Result<Self::Output2, Self::Error> {
+            Ok(format!("{input:?}"))
+        }
+    }
+
+    #[test]
+    fn solves_parts_from_input() {
+        assert_eq!(Sum::solve_part_one("1,2,3").unwrap(), 6);
+        assert_eq!(Sum::solve_part_two("1,2").unwrap(), "[1, 2]");
+        assert!(Sum::solve_part_one("1,x").is_err());
+    }
+}
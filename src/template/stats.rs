@@ -0,0 +1,384 @@
+/// Personal solve-time tracking.
+/// Records when a day was scaffolded and when each part's answer was accepted by the aoc server.
+use std::{
+    collections::HashMap,
+    fs,
+    io::Error,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tinyjson::JsonValue;
+
+use crate::template::{aoc_cli, Day};
+
+static STATS_FILE_PATH: &str = "./data/stats.json";
+
+/// Puzzles unlock at midnight EST, i.e. 05:00 UTC.
+const UNLOCK_HOUR_UTC: u64 = 5;
+
+/// Represents recorded timestamps (seconds since the unix epoch) for a single day.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stat {
+    pub day: Day,
+    pub scaffolded_at: Option<u64>,
+    pub part_1_at: Option<u64>,
+    pub part_2_at: Option<u64>,
+}
+
+/// Represents recorded timestamps for a set of days.
+/// Can be serialized from / to JSON.
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    pub data: Vec<Stat>,
+}
+
+impl Stat {
+    fn new(day: Day) -> Self {
+        Stat {
+            day,
+            scaffolded_at: None,
+            part_1_at: None,
+            part_2_at: None,
+        }
+    }
+
+    /// The moment the clock starts for this day: the puzzle unlock if the day was scaffolded
+    /// before it (or not at all), the scaffold time if it happened later.
+    pub fn started_at(&self, year: Option<u16>) -> Option<u64> {
+        let unlocked_at = year.map(|year| unlock_time(year, self.day));
+        match (self.scaffolded_at, unlocked_at) {
+            (Some(scaffolded), Some(unlocked)) => Some(scaffolded.max(unlocked)),
+            (scaffolded, unlocked) => scaffolded.or(unlocked),
+        }
+    }
+
+    /// Seconds from the start of the day until the given part was accepted.
+    pub fn time_to_solve(&self, part: u8, year: Option<u16>) -> Option<u64> {
+        let accepted_at = match part {
+            1 => self.part_1_at,
+            2 => self.part_2_at,
+            _ => None,
+        }?;
+        Some(accepted_at.saturating_sub(self.started_at(year)?))
+    }
+}
+
+impl Stats {
+    /// Dehydrate stats to a JSON file.
+    pub fn store_file(&self) -> Result<(), Error> {
+        let json = JsonValue::from(self.clone());
+        let mut file = fs::File::create(STATS_FILE_PATH)?;
+        json.format_to(&mut file)
+    }
+
+    /// Rehydrate stats from a JSON file. If not present, returns empty stats.
+    pub fn read_from_file() -> Self {
+        fs::read_to_string(STATS_FILE_PATH)
+            .map_err(|x| x.to_string())
+            .and_then(Stats::try_from)
+            .unwrap_or_default()
+    }
+
+    pub fn get(&self, day: Day) -> Option<&Stat> {
+        self.data.iter().find(|s| s.day == day)
+    }
+
+    fn get_or_insert(&mut self, day: Day) -> &mut Stat {
+        let index = match self.data.iter().position(|s| s.day == day) {
+            Some(index) => index,
+            None => {
+                self.data.push(Stat::new(day));
+                self.data.sort_unstable_by_key(|s| s.day);
+                self.data.iter().position(|s| s.day == day).unwrap()
+            }
+        };
+        &mut self.data[index]
+    }
+
+    /// Record that a day was scaffolded at `timestamp`. Re-scaffolding keeps the first timestamp.
+    pub fn record_scaffold(&mut self, day: Day, timestamp: u64) {
+        self.get_or_insert(day)
+            .scaffolded_at
+            .get_or_insert(timestamp);
+    }
+
+    /// Record that the answer for `part` was accepted at `timestamp`.
+    pub fn record_accepted(&mut self, day: Day, part: u8, timestamp: u64) {
+        let stat = self.get_or_insert(day);
+        match part {
+            1 => stat.part_1_at.get_or_insert(timestamp),
+            _ => stat.part_2_at.get_or_insert(timestamp),
+        };
+    }
+
+    pub fn is_part_accepted(&self, day: Day, part: u8) -> bool {
+        self.get(day).is_some_and(|s| match part {
+            1 => s.part_1_at.is_some(),
+            _ => s.part_2_at.is_some(),
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Update the stats file on disk. Failures are reported, but never abort the caller.
+pub fn record(update: impl FnOnce(&mut Stats, u64)) {
+    let mut stats = Stats::read_from_file();
+    update(&mut stats, now());
+    if let Err(e) = stats.store_file() {
+        eprintln!("Failed to store stats: {e}");
+    }
+}
+
+/// Current time as seconds since the unix epoch.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// The configured puzzle year, if any.
+pub fn year() -> Option<u16> {
+    aoc_cli::get_year()
+}
+
+/// Unix timestamp at which the puzzle for `day` of `year` unlocks.
+pub fn unlock_time(year: u16, day: Day) -> u64 {
+    let days = days_from_civil(i64::from(year), 12, i64::from(day.into_inner()));
+    #[allow(clippy::cast_sign_loss)]
+    let days = days as u64;
+    days * 86_400 + UNLOCK_HOUR_UTC * 3600
+}
+
+/// Format a unix timestamp as `YYYY-MM-DD HH:MM` (UTC).
+pub fn format_timestamp(timestamp: u64) -> String {
+    #[allow(clippy::cast_possible_wrap)]
+    let (y, m, d) = civil_from_days((timestamp / 86_400) as i64);
+    let secs = timestamp % 86_400;
+    format!(
+        "{y:04}-{m:02}-{d:02} {:02}:{:02}",
+        secs / 3600,
+        (secs % 3600) / 60
+    )
+}
+
+/// Format a duration in seconds like the aoc personal stats page, e.g. `01:02:03` or `3d 01:02:03`.
+pub fn format_duration(secs: u64) -> String {
+    let days = secs / 86_400;
+    let rest = secs % 86_400;
+    let hms = format!(
+        "{:02}:{:02}:{:02}",
+        rest / 3600,
+        (rest % 3600) / 60,
+        rest % 60
+    );
+    if days > 0 {
+        format!("{days}d {hms}")
+    } else {
+        hms
+    }
+}
+
+/// Days since 1970-01-01 for a proleptic gregorian date.
+/// see: http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_from_civil(y: i64, m: i64, d: i64) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Inverse of [`days_from_civil`].
+/// see: http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_from_days(z: i64) -> (i64, i64, i64) {
+    let z = z + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    (y, m, d)
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<Stats> for JsonValue {
+    fn from(value: Stats) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for Stats {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_data = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("data")
+            .ok_or("expected JSON document to have key `data`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.data` to be an array.")?;
+
+        Ok(Stats {
+            data: json_data
+                .iter()
+                .map(Stat::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[allow(clippy::cast_precision_loss)]
+fn timestamp_to_json(value: Option<u64>) -> JsonValue {
+    match value {
+        Some(x) => JsonValue::Number(x as f64),
+        None => JsonValue::Null,
+    }
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn timestamp_from_json(value: Option<&JsonValue>) -> Option<Option<u64>> {
+    value.map(|v| v.get::<f64>().map(|x| *x as u64))
+}
+
+impl From<&Stat> for JsonValue {
+    fn from(value: &Stat) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+        map.insert(
+            "scaffolded_at".into(),
+            timestamp_to_json(value.scaffolded_at),
+        );
+        map.insert("part_1_at".into(), timestamp_to_json(value.part_1_at));
+        map.insert("part_2_at".into(), timestamp_to_json(value.part_2_at));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Stat {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected stat to be a JSON object.")?;
+
+        let day = json
+            .get("day")
+            .and_then(|v| v.get::<String>())
+            .and_then(|day| Day::from_str(day).ok())
+            .ok_or("Expected stat.day to be a Day struct.")?;
+
+        let scaffolded_at = timestamp_from_json(json.get("scaffolded_at"))
+            .ok_or("Expected stat.scaffolded_at to be null or number.")?;
+
+        let part_1_at = timestamp_from_json(json.get("part_1_at"))
+            .ok_or("Expected stat.part_1_at to be null or number.")?;
+
+        let part_2_at = timestamp_from_json(json.get("part_2_at"))
+            .ok_or("Expected stat.part_2_at to be null or number.")?;
+
+        Ok(Stat {
+            day,
+            scaffolded_at,
+            part_1_at,
+            part_2_at,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use crate::day;
+
+    use super::{format_duration, format_timestamp, unlock_time, Stat, Stats};
+
+    #[test]
+    fn computes_unlock_time() {
+        // 2024-12-01T05:00:00Z
+        assert_eq!(unlock_time(2024, day!(1)), 1_733_029_200);
+        assert_eq!(
+            format_timestamp(unlock_time(2024, day!(25))),
+            "2024-12-25 05:00"
+        );
+    }
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!(format_duration(59), "00:00:59");
+        assert_eq!(format_duration(3723), "01:02:03");
+        assert_eq!(format_duration(3 * 86_400 + 61), "3d 00:01:01");
+    }
+
+    #[test]
+    fn measures_from_unlock_or_later_scaffold() {
+        let unlocked = unlock_time(2024, day!(1));
+        let early = Stat {
+            day: day!(1),
+            scaffolded_at: Some(unlocked - 600),
+            part_1_at: Some(unlocked + 300),
+            part_2_at: Some(unlocked + 900),
+        };
+        assert_eq!(early.time_to_solve(1, Some(2024)), Some(300));
+        assert_eq!(early.time_to_solve(2, Some(2024)), Some(900));
+
+        let late = Stat {
+            scaffolded_at: Some(unlocked + 86_400),
+            part_1_at: Some(unlocked + 86_400 + 120),
+            ..early
+        };
+        assert_eq!(late.time_to_solve(1, Some(2024)), Some(120));
+        assert_eq!(late.time_to_solve(2, None), Some(0));
+    }
+
+    #[test]
+    fn keeps_first_timestamps() {
+        let mut stats = Stats::default();
+        stats.record_scaffold(day!(3), 10);
+        stats.record_scaffold(day!(3), 20);
+        stats.record_accepted(day!(3), 1, 30);
+        stats.record_accepted(day!(3), 1, 40);
+        stats.record_scaffold(day!(1), 5);
+
+        assert_eq!(stats.data[0].day, day!(1));
+        let stat = stats.get(day!(3)).unwrap();
+        assert_eq!(stat.scaffolded_at, Some(10));
+        assert_eq!(stat.part_1_at, Some(30));
+        assert!(stats.is_part_accepted(day!(3), 1));
+        assert!(!stats.is_part_accepted(day!(3), 2));
+    }
+
+    #[test]
+    fn roundtrips_json() {
+        let json = r#"{ "data": [{ "day": "02", "scaffolded_at": 100, "part_1_at": 160, "part_2_at": null }] }"#.to_string();
+        let stats = Stats::try_from(json).unwrap();
+        assert_eq!(stats.data[0].part_1_at, Some(160));
+        assert_eq!(stats.data[0].part_2_at, None);
+
+        let value = tinyjson::JsonValue::from(stats.clone());
+        let reparsed = Stats::try_from(value.stringify().unwrap()).unwrap();
+        assert_eq!(reparsed.data, stats.data);
+    }
+}
@@ -0,0 +1,151 @@
+/// Checks that describe how far along a day is: scaffolded files, downloaded data,
+/// implemented parts, passing tests, accepted answers and stored timings.
+use std::{
+    fs,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+use crate::template::run_multi::get_path_for_bin;
+use crate::template::stats::Stats;
+use crate::template::timings::Timings;
+use crate::template::Day;
+
+/// State of a data file on disk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileState {
+    Missing,
+    Empty,
+    Present,
+}
+
+/// Everything `cargo status` reports for a single day.
+#[derive(Clone, Debug)]
+pub struct DayStatus {
+    pub day: Day,
+    pub bin: bool,
+    pub input: FileState,
+    pub example: FileState,
+    pub puzzle: FileState,
+    /// Whether `part_one` / `part_two` still contain the scaffolded stub. `None` if there is no bin.
+    pub stubs: Option<[bool; 2]>,
+    /// Whether `cargo test --bin <day>` passed. `None` if tests were not run.
+    pub tests: Option<bool>,
+    pub answers: [bool; 2],
+    pub timings: [bool; 2],
+}
+
+impl DayStatus {
+    pub fn collect(day: Day, stats: &Stats, timings: &Timings, run_tests: bool) -> Self {
+        let bin = bin_exists(day);
+
+        let stubs = bin.then(|| {
+            let source = fs::read_to_string(get_path_for_bin(day)).unwrap_or_default();
+            [is_stub(&source, "part_one"), is_stub(&source, "part_two")]
+        });
+
+        let timing = timings.data.iter().find(|t| t.day == day);
+
+        DayStatus {
+            day,
+            bin,
+            input: file_state(&format!("data/inputs/{day}.txt")),
+            example: file_state(&format!("data/examples/{day}.txt")),
+            puzzle: file_state(&format!("data/puzzles/{day}.md")),
+            stubs,
+            tests: (bin && run_tests).then(|| tests_pass(day)),
+            answers: [
+                stats.is_part_accepted(day, 1),
+                stats.is_part_accepted(day, 2),
+            ],
+            timings: [
+                timing.is_some_and(|t| t.part_1.is_some()),
+                timing.is_some_and(|t| t.part_2.is_some()),
+            ],
+        }
+    }
+}
+
+/// Whether the solution bin for a day has been scaffolded.
+pub fn bin_exists(day: Day) -> bool {
+    Path::new(&get_path_for_bin(day)).exists()
+}
+
+/// Whether a day still lacks stored timings for at least one part.
+pub fn needs_benching(timings: &Timings, day: Day) -> bool {
+    !timings.is_day_complete(day)
+}
+
+pub fn file_state(path: &str) -> FileState {
+    match fs::metadata(path) {
+        Ok(meta) if meta.len() > 0 => FileState::Present,
+        Ok(_) => FileState::Empty,
+        Err(_) => FileState::Missing,
+    }
+}
+
+/// Whether the function `name` in `source` still has the body of the scaffold template, i.e. `None`.
+pub fn is_stub(source: &str, name: &str) -> bool {
+    let Some(start) = source.find(&format!("fn {name}(")) else {
+        // a part that is not defined cannot return a result either.
+        return true;
+    };
+
+    let rest = &source[start..];
+    let Some(open) = rest.find('{') else {
+        return true;
+    };
+
+    let mut depth = 0usize;
+    for (i, ch) in rest[open..].char_indices() {
+        match ch {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return rest[open + 1..open + i].trim() == "None";
+                }
+            }
+            _ => {}
+        }
+    }
+
+    true
+}
+
+fn tests_pass(day: Day) -> bool {
+    Command::new("cargo")
+        .args(["test", "--quiet", "--bin", &day.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::is_stub;
+
+    #[test]
+    fn detects_template_stubs() {
+        let source = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template.txt"));
+        assert!(is_stub(source, "part_one"));
+        assert!(is_stub(source, "part_two"));
+    }
+
+    #[test]
+    fn detects_implemented_parts() {
+        let source = "
+pub fn part_one(input: &str) -> Option<u64> {
+    if input.is_empty() { return None; }
+    Some(1)
+}
+
+pub fn part_two(_input: &str) -> Option<u64> {
+    None
+}";
+        assert!(!is_stub(source, "part_one"));
+        assert!(is_stub(source, "part_two"));
+        assert!(is_stub(source, "part_three"));
+    }
+}
@@ -0,0 +1,227 @@
+/// Module templates used by `cargo scaffold`.
+/// Built-in templates ship with the crate, user templates live in the `templates/` directory
+/// of the project as `<name>.txt` and take precedence over built-in ones of the same name.
+use std::{fs, path::Path};
+
+use crate::template::{aoc_cli, Day};
+
+static USER_TEMPLATES_DIR: &str = "templates";
+
+pub const DEFAULT_TEMPLATE: &str = "plain";
+
+const BUILTIN_TEMPLATES: [(&str, &str); 4] = [
+    (
+        "plain",
+        include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template.txt")),
+    ),
+    (
+        "grid",
+        include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/src/templates/grid.txt"
+        )),
+    ),
+    (
+        "sections",
+        include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/src/templates/sections.txt"
+        )),
+    ),
+    (
+        "graph",
+        include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/src/templates/graph.txt"
+        )),
+    ),
+];
+
+/// Values substituted for the `%PLACEHOLDER%`s of a template.
+#[derive(Clone, Debug)]
+pub struct TemplateValues {
+    pub day: Day,
+    pub year: Option<u16>,
+    pub puzzle_title: Option<String>,
+    /// Expected results for the example input of part 1 and part 2.
+    pub examples: [Option<String>; 2],
+}
+
+impl TemplateValues {
+    /// Collect values for `day` from the environment and the downloaded puzzle description, if any.
+    pub fn for_day(day: Day, examples: [Option<String>; 2]) -> Self {
+        let puzzle_title = fs::read_to_string(format!("data/puzzles/{day}.md"))
+            .ok()
+            .and_then(|puzzle| parse_puzzle_title(&puzzle));
+
+        TemplateValues {
+            day,
+            year: aoc_cli::get_year(),
+            puzzle_title,
+            examples,
+        }
+    }
+
+    fn puzzle_url(&self) -> String {
+        match self.year {
+            Some(year) => format!(
+                "https://adventofcode.com/{year}/day/{}",
+                self.day.into_inner()
+            ),
+            None => "https://adventofcode.com/".into(),
+        }
+    }
+}
+
+/// Names of all available templates, user templates first.
+pub fn names() -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(USER_TEMPLATES_DIR)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter_map(|entry| {
+                    let path = entry.path();
+                    (path.extension()? == "txt")
+                        .then(|| path.file_stem()?.to_str().map(String::from))?
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort_unstable();
+
+    for (name, _) in BUILTIN_TEMPLATES {
+        if !names.iter().any(|n| n == name) {
+            names.push(name.into());
+        }
+    }
+
+    names
+}
+
+/// Load a template by name, preferring user templates over built-in ones.
+pub fn load(name: &str) -> Result<String, String> {
+    let user_path = Path::new(USER_TEMPLATES_DIR).join(format!("{name}.txt"));
+    if let Ok(template) = fs::read_to_string(&user_path) {
+        return Ok(template);
+    }
+
+    BUILTIN_TEMPLATES
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, template)| (*template).to_string())
+        .ok_or_else(|| {
+            format!(
+                "unknown template \"{name}\". Available templates: {}.",
+                names().join(", ")
+            )
+        })
+}
+
+/// Substitute all placeholders of `template`.
+///
+/// | Placeholder | Value |
+/// | --- | --- |
+/// | `%DAY_NUMBER%` | day without padding, e.g. `8` |
+/// | `%DAY%` | day padded to two digits, e.g. `08` |
+/// | `%YEAR%` | value of `AOC_YEAR` |
+/// | `%PUZZLE_TITLE%` | title from the downloaded puzzle, e.g. `Day 8: Resonant Collinearity` |
+/// | `%PUZZLE_URL%` | link to the puzzle on adventofcode.com |
+/// | `%EXAMPLE_PART_ONE%` / `%EXAMPLE_PART_TWO%` | `Some(<answer>)` if given, `None` otherwise |
+pub fn render(template: &str, values: &TemplateValues) -> String {
+    let example = |i: usize| {
+        values.examples[i]
+            .as_ref()
+            .map_or_else(|| "None".into(), |answer| format!("Some({answer})"))
+    };
+
+    let title = values
+        .puzzle_title
+        .clone()
+        .unwrap_or_else(|| format!("Day {}", values.day.into_inner()));
+
+    template
+        .replace("%DAY_NUMBER%", &values.day.into_inner().to_string())
+        .replace("%DAY%", &values.day.to_string())
+        .replace(
+            "%YEAR%",
+            &values.year.map(|y| y.to_string()).unwrap_or_default(),
+        )
+        .replace("%PUZZLE_TITLE%", &title)
+        .replace("%PUZZLE_URL%", &values.puzzle_url())
+        .replace("%EXAMPLE_PART_ONE%", &example(0))
+        .replace("%EXAMPLE_PART_TWO%", &example(1))
+}
+
+/// Extract the title from a puzzle description, whose heading looks like `--- Day 8: Title ---`.
+fn parse_puzzle_title(puzzle: &str) -> Option<String> {
+    puzzle.lines().find_map(|line| {
+        let title = line
+            .trim()
+            .trim_start_matches('#')
+            .trim()
+            .strip_prefix("--- ")?
+            .strip_suffix(" ---")?;
+        title.starts_with("Day ").then(|| title.to_string())
+    })
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{load, parse_puzzle_title, render, TemplateValues};
+    use crate::day;
+
+    fn get_mock_values() -> TemplateValues {
+        TemplateValues {
+            day: day!(8),
+            year: Some(2024),
+            puzzle_title: Some("Day 8: Resonant Collinearity".into()),
+            examples: [Some("14".into()), None],
+        }
+    }
+
+    #[test]
+    fn renders_placeholders() {
+        let template = "%DAY_NUMBER% %DAY% %YEAR% %PUZZLE_TITLE% %PUZZLE_URL% %EXAMPLE_PART_ONE% %EXAMPLE_PART_TWO%";
+        assert_eq!(
+            render(template, &get_mock_values()),
+            "8 08 2024 Day 8: Resonant Collinearity https://adventofcode.com/2024/day/8 Some(14) None"
+        );
+    }
+
+    #[test]
+    fn falls_back_without_puzzle() {
+        let values = TemplateValues {
+            year: None,
+            puzzle_title: None,
+            ..get_mock_values()
+        };
+        assert_eq!(
+            render("%PUZZLE_TITLE% %PUZZLE_URL%", &values),
+            "Day 8 https://adventofcode.com/"
+        );
+    }
+
+    #[test]
+    fn renders_builtin_templates() {
+        for name in ["plain", "grid", "sections", "graph"] {
+            let rendered = render(&load(name).unwrap(), &get_mock_values());
+            assert!(rendered.contains("advent_of_code::solution!(8);"));
+            assert!(!rendered.contains('%'), "unreplaced placeholder in {name}");
+        }
+    }
+
+    #[test]
+    fn errors_for_unknown_templates() {
+        assert!(load("does-not-exist").unwrap_err().contains("plain"));
+    }
+
+    #[test]
+    fn parses_puzzle_titles() {
+        let puzzle = "\n## --- Day 8: Resonant Collinearity ---\nYou find yourself...";
+        assert_eq!(
+            parse_puzzle_title(puzzle),
+            Some("Day 8: Resonant Collinearity".into())
+        );
+        assert_eq!(parse_puzzle_title("--- Part Two ---"), None);
+    }
+}
@@ -9,9 +9,21 @@ static TIMINGS_FILE_PATH: &str = "./data/timings.json";
 #[derive(Clone, Debug)]
 pub struct Timing {
     pub day: Day,
+    /// Time to parse the input, for days implementing [`crate::template::Solution`].
+    pub parse: Option<String>,
     pub part_1: Option<String>,
     pub part_2: Option<String>,
     pub total_nanos: f64,
+    /// Times of the individual implementations, for days that register several variants per part.
+    pub variants: Vec<VariantTiming>,
+}
+
+/// Benchmark time of one named variant of a part, see `solution!`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VariantTiming {
+    pub part: u8,
+    pub name: String,
+    pub time: String,
 }
 
 /// Represents benchmark times for a set of days.
@@ -51,7 +63,7 @@ impl Timings {
             }
         }
 
-        data.sort_unstable_by(|a, b| a.day.cmp(&b.day));
+        data.sort_unstable_by_key(|a| a.day);
         Timings { data }
     }
 
@@ -114,6 +126,10 @@ impl From<&Timing> for JsonValue {
         map.insert("day".into(), JsonValue::String(value.day.to_string()));
         map.insert("total_nanos".into(), JsonValue::Number(value.total_nanos));
 
+        if let Some(parse) = &value.parse {
+            map.insert("parse".into(), JsonValue::String(parse.clone()));
+        }
+
         let part_1 = value.part_1.clone().map(JsonValue::String);
         let part_2 = value.part_2.clone().map(JsonValue::String);
 
@@ -133,6 +149,13 @@ impl From<&Timing> for JsonValue {
             },
         );
 
+        if !value.variants.is_empty() {
+            map.insert(
+                "variants".into(),
+                JsonValue::Array(value.variants.iter().map(JsonValue::from).collect()),
+            );
+        }
+
         JsonValue::Object(map)
     }
 }
@@ -151,6 +174,16 @@ impl TryFrom<&JsonValue> for Timing {
             .and_then(|day| Day::from_str(day).ok())
             .ok_or("Expected timing.day to be a Day struct.")?;
 
+        // older timing files and days with free part functions have no parse time.
+        let parse = match json.get("parse") {
+            Some(v) => Some(
+                v.get::<String>()
+                    .ok_or("Expected timing.parse to be a string.")?
+                    .clone(),
+            ),
+            None => None,
+        };
+
         let part_1 = json
             .get("part_1")
             .map(|v| if v.is_null() { None } else { v.get::<String>() })
@@ -166,11 +199,71 @@ impl TryFrom<&JsonValue> for Timing {
             .and_then(|v| v.get::<f64>().copied())
             .ok_or("Expected timing.total_nanos to be a number.")?;
 
+        // older timing files do not contain variants.
+        let variants = match json.get("variants") {
+            Some(v) => v
+                .get::<Vec<JsonValue>>()
+                .ok_or("Expected timing.variants to be an array.")?
+                .iter()
+                .map(VariantTiming::try_from)
+                .collect::<Result<_, _>>()?,
+            None => vec![],
+        };
+
         Ok(Timing {
             day,
+            parse,
             part_1: part_1.cloned(),
             part_2: part_2.cloned(),
             total_nanos,
+            variants,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&VariantTiming> for JsonValue {
+    fn from(value: &VariantTiming) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("part".into(), JsonValue::Number(f64::from(value.part)));
+        map.insert("name".into(), JsonValue::String(value.name.clone()));
+        map.insert("time".into(), JsonValue::String(value.time.clone()));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for VariantTiming {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected variant timing to be a JSON object.")?;
+
+        let part = json
+            .get("part")
+            .and_then(|v| v.get::<f64>().copied())
+            .filter(|part| *part == 1.0 || *part == 2.0)
+            .ok_or("Expected variant.part to be 1 or 2.")?;
+
+        let name = json
+            .get("name")
+            .and_then(|v| v.get::<String>())
+            .ok_or("Expected variant.name to be a string.")?;
+
+        let time = json
+            .get("time")
+            .and_then(|v| v.get::<String>())
+            .ok_or("Expected variant.time to be a string.")?;
+
+        Ok(VariantTiming {
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            part: part as u8,
+            name: name.clone(),
+            time: time.clone(),
         })
     }
 }
@@ -188,21 +281,27 @@ mod tests {
             data: vec![
                 Timing {
                     day: day!(1),
+                    parse: None,
                     part_1: Some("10ms".into()),
                     part_2: Some("20ms".into()),
                     total_nanos: 3e+10,
+                    variants: vec![],
                 },
                 Timing {
                     day: day!(2),
+                    parse: None,
                     part_1: Some("30ms".into()),
                     part_2: Some("40ms".into()),
                     total_nanos: 7e+10,
+                    variants: vec![],
                 },
                 Timing {
                     day: day!(4),
+                    parse: None,
                     part_1: Some("40ms".into()),
                     part_2: None,
                     total_nanos: 4e+10,
+                    variants: vec![],
                 },
             ],
         }
@@ -221,6 +320,25 @@ mod tests {
             assert_eq!(timing.part_1, Some("1ms".to_string()));
             assert_eq!(timing.part_2, None);
             assert_eq!(timing.total_nanos, 1_000_000_000_f64);
+            assert_eq!(timing.parse, None);
+        }
+
+        #[test]
+        fn handles_parse_timings() {
+            let json = r#"{ "data": [{ "day": "05", "parse": "3ms", "part_1": "1ms", "part_2": "2ms", "total_nanos": 6000000 }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            assert_eq!(timings.data[0].parse, Some("3ms".to_string()));
+        }
+
+        #[test]
+        fn handles_variant_timings() {
+            let json = r#"{ "data": [{ "day": "07", "part_1": "1ms", "part_2": null, "total_nanos": 1000000, "variants": [{ "part": 1, "name": "dfs", "time": "2ms" }] }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let timing = timings.data.first().unwrap();
+            assert_eq!(timing.variants.len(), 1);
+            assert_eq!(timing.variants[0].part, 1);
+            assert_eq!(timing.variants[0].name, "dfs");
+            assert_eq!(timing.variants[0].time, "2ms");
         }
 
         #[test]
@@ -247,6 +365,7 @@ mod tests {
 
     mod serialization {
         use super::get_mock_timings;
+        use crate::template::timings::{Timings, VariantTiming};
         use std::collections::HashMap;
         use tinyjson::JsonValue;
 
@@ -266,6 +385,20 @@ mod tests {
                 3
             );
         }
+
+        #[test]
+        fn round_trips_variants() {
+            let mut timings = get_mock_timings();
+            timings.data[0].variants = vec![VariantTiming {
+                part: 2,
+                name: "bitmask".into(),
+                time: "5ms".into(),
+            }];
+            let json = JsonValue::from(timings.clone()).stringify().unwrap();
+            let parsed = Timings::try_from(json).unwrap();
+            assert_eq!(parsed.data[0].variants, timings.data[0].variants);
+            assert!(parsed.data[1].variants.is_empty());
+        }
     }
 
     mod is_day_complete {
@@ -279,13 +412,15 @@ mod tests {
             let timings = Timings {
                 data: vec![Timing {
                     day: day!(1),
+                    parse: None,
                     part_1: Some("1ms".into()),
                     part_2: Some("2ms".into()),
                     total_nanos: 3_000_000_000_f64,
+                    variants: vec![],
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), true);
+            assert_eq!(timings.is_day_complete(day!(1)), true);
         }
 
         #[test]
@@ -293,13 +428,15 @@ mod tests {
             let timings = Timings {
                 data: vec![Timing {
                     day: day!(1),
+                    parse: None,
                     part_1: Some("1ms".into()),
                     part_2: None,
                     total_nanos: 1_000_000_000_f64,
+                    variants: vec![],
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
 
         #[test]
@@ -307,13 +444,15 @@ mod tests {
             let timings = Timings {
                 data: vec![Timing {
                     day: day!(1),
+                    parse: None,
                     part_1: None,
                     part_2: None,
                     total_nanos: 0.0,
+                    variants: vec![],
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
     }
 
@@ -331,9 +470,11 @@ mod tests {
             let other = Timings {
                 data: vec![Timing {
                     day: day!(3),
+                    parse: None,
                     part_1: None,
                     part_2: None,
                     total_nanos: 0_f64,
+                    variants: vec![],
                 }],
             };
             let merged = timings.merge(&other);
@@ -351,9 +492,11 @@ mod tests {
             let other = Timings {
                 data: vec![Timing {
                     day: day!(2),
+                    parse: None,
                     part_1: None,
                     part_2: None,
                     total_nanos: 0_f64,
+                    variants: vec![],
                 }],
             };
             let merged = timings.merge(&other);
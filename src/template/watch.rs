@@ -0,0 +1,238 @@
+/// Polling file watcher and answer tracking used by `cargo solve <day> --watch`.
+/// Relies on modification times only, so it works without platform specific notification APIs.
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use crate::template::{Day, ANSI_BOLD, ANSI_RESET};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Modification times of all watched files. Files that do not exist are not part of a snapshot.
+pub type Snapshot = BTreeMap<PathBuf, SystemTime>;
+
+/// Files that trigger a re-run for `day`: its bin, every library source outside `src/bin`,
+/// `build.rs` and all of the day's data files.
+pub fn watched_paths(day: Day) -> Vec<PathBuf> {
+    let mut paths = vec![PathBuf::from(format!("src/bin/{day}.rs"))];
+    let mut sources = vec![];
+    collect_sources(Path::new("src"), &mut sources);
+    sources.sort_unstable();
+    paths.extend(sources);
+    paths.push(PathBuf::from("build.rs"));
+
+    for folder in ["inputs", "examples"] {
+        // matches `<day>.txt` as well as part specific files like `<day>-2.txt`.
+        let Ok(entries) = fs::read_dir(Path::new("data").join(folder)) else {
+            continue;
+        };
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| {
+                        name.starts_with(&day.to_string()) && name.ends_with(".txt")
+                    })
+            })
+            .collect();
+        files.sort_unstable();
+        paths.extend(files);
+    }
+
+    paths
+}
+
+/// All `.rs` files below `dir`, except for the bins of other days.
+fn collect_sources(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.filter_map(Result::ok).map(|entry| entry.path()) {
+        if path == Path::new("src/bin") {
+            continue;
+        }
+        if path.is_dir() {
+            collect_sources(&path, out);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            out.push(path);
+        }
+    }
+}
+
+pub fn snapshot(day: Day) -> Snapshot {
+    watched_paths(day)
+        .into_iter()
+        .filter_map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((path, modified))
+        })
+        .collect()
+}
+
+/// Block until a watched file of `day` is created, modified or deleted. Returns the new snapshot.
+pub fn wait_for_change(day: Day, previous: &Snapshot) -> Snapshot {
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        let current = snapshot(day);
+        if current != *previous {
+            // editors often write files in several steps, give them a moment to finish.
+            thread::sleep(POLL_INTERVAL);
+            return snapshot(day);
+        }
+    }
+}
+
+pub fn clear_screen() {
+    print!("\x1b[2J\x1b[H");
+}
+
+/// Extract the answers of both parts from the output of a solution bin.
+/// Parts that were not solved or did not run are `None`.
+pub fn parse_answers(output: &[String]) -> [Option<String>; 2] {
+    let mut answers = [None, None];
+
+    let lines: Vec<String> = output
+        .iter()
+        // intermediate results are overwritten with `\r`, only the final one matters.
+        .map(|line| strip_ansi(line.rsplit('\r').next().unwrap_or_default()))
+        .collect();
+
+    for (i, line) in lines.iter().enumerate() {
+        let Some((part, rest)) = line
+            .strip_prefix("Part ")
+            .and_then(|rest| rest.split_once(": "))
+        else {
+            continue;
+        };
+        let Some(index) = ["1", "2"].iter().position(|p| *p == part) else {
+            continue;
+        };
+
+        let rest = rest.trim_end();
+        let value = match rest.rsplit_once(" (") {
+            Some((value, _)) if rest.ends_with(')') => value.trim(),
+            _ => rest,
+        };
+
+        answers[index] = match value {
+            "✖" => None,
+            // multi-line results are printed below their part.
+            v if v.starts_with('▼') => Some(
+                lines[i + 1..]
+                    .iter()
+                    .take_while(|l| !l.is_empty() && !l.starts_with("Part "))
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+            v => Some(v.to_string()),
+        };
+    }
+
+    answers
+}
+
+fn strip_ansi(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(ch) = chars.next() {
+        if ch == '\x1b' {
+            // skip until the final byte of the escape sequence.
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+/// Describe how the answers changed since the previous run.
+pub fn diff_answers(previous: &[Option<String>; 2], current: &[Option<String>; 2]) -> Vec<String> {
+    let show = |answer: &Option<String>| answer.clone().unwrap_or_else(|| "✖".into());
+
+    previous
+        .iter()
+        .zip(current)
+        .enumerate()
+        .map(|(i, (before, after))| {
+            let part = i + 1;
+            if before == after {
+                format!("Part {part}: {} (unchanged)", show(after))
+            } else {
+                format!(
+                    "Part {part}: {} → {ANSI_BOLD}{}{ANSI_RESET}",
+                    show(before),
+                    show(after)
+                )
+            }
+        })
+        .collect()
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{diff_answers, parse_answers, watched_paths};
+    use crate::day;
+    use crate::template::{ANSI_BOLD, ANSI_RESET};
+
+    #[test]
+    fn watches_library_sources() {
+        let paths = watched_paths(day!(1));
+        for path in [
+            "src/bin/01.rs",
+            "src/lib.rs",
+            "src/template/runner.rs",
+            "src/template/watch.rs",
+            "build.rs",
+        ] {
+            assert!(paths.contains(&PathBuf::from(path)), "{path}");
+        }
+        assert!(!paths.contains(&PathBuf::from("src/bin/02.rs")));
+        assert!(!paths.contains(&PathBuf::from("src/bin/advent.rs")));
+    }
+
+    #[test]
+    fn parses_answers() {
+        let output: Vec<String> = vec![
+            format!("Part 1: {ANSI_BOLD}42{ANSI_RESET} > benching\rPart 1: {ANSI_BOLD}42{ANSI_RESET} (1.2ms @ 10 samples)"),
+            "Part 2: ✖\rPart 2: ✖             ".into(),
+        ];
+        assert_eq!(parse_answers(&output), [Some("42".into()), None]);
+    }
+
+    #[test]
+    fn parses_multiline_answers() {
+        let output: Vec<String> = vec![
+            "Part 1: 7 (3.0ns @ 10 samples)".into(),
+            "Part 2: ▼  (1.0ms)".into(),
+            "#.#".into(),
+            ".#.".into(),
+        ];
+        assert_eq!(
+            parse_answers(&output),
+            [Some("7".into()), Some("#.#\n.#.".into())]
+        );
+    }
+
+    #[test]
+    fn diffs_answers() {
+        let diff = diff_answers(
+            &[Some("1".into()), None],
+            &[Some("1".into()), Some("2".into())],
+        );
+        assert_eq!(diff[0], "Part 1: 1 (unchanged)");
+        assert_eq!(diff[1], format!("Part 2: ✖ → {ANSI_BOLD}2{ANSI_RESET}"));
+    }
+}
@@ -0,0 +1,663 @@
+//! Export of visualization frames to image files: PPM or PNG sequences and animated GIFs.
+//! All encoders are implemented here, PNG data is stored uncompressed and GIF data is LZW encoded.
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use super::{Color, Frame};
+
+/// RGB values of the palette. Index 0 is the background, the rest follow [`palette_index`].
+pub const PALETTE: [[u8; 3]; 16] = [
+    [0x0f, 0x0f, 0x23],
+    [0xcc, 0xcc, 0xcc],
+    [0x55, 0x55, 0x66],
+    [0xff, 0x44, 0x44],
+    [0x00, 0xcc, 0x00],
+    [0xff, 0xff, 0x66],
+    [0x44, 0x88, 0xff],
+    [0xcc, 0x44, 0xcc],
+    [0x44, 0xcc, 0xcc],
+    [0xff, 0xff, 0xff],
+    [0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x00],
+];
+
+/// Blank cells are drawn in the background colour, all others as a block of their colour.
+fn palette_index(ch: char, color: Color) -> u8 {
+    if ch == ' ' {
+        return 0;
+    }
+    match color {
+        Color::Default => 1,
+        Color::Gray => 2,
+        Color::Red => 3,
+        Color::Green => 4,
+        Color::Yellow => 5,
+        Color::Blue => 6,
+        Color::Magenta => 7,
+        Color::Cyan => 8,
+        Color::White => 9,
+    }
+}
+
+/// An image with one palette index per pixel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    /// Draw every cell of `frame` as a `scale` x `scale` block.
+    pub fn from_frame(frame: &Frame, scale: usize) -> Self {
+        let scale = scale.max(1);
+        let (width, height) = (frame.width() * scale, frame.height() * scale);
+        let mut pixels = vec![0; width * height];
+
+        for y in 0..height {
+            for x in 0..width {
+                if let Some(cell) = frame.get(y / scale, x / scale) {
+                    pixels[y * width + x] = palette_index(cell.ch, cell.color);
+                }
+            }
+        }
+
+        Image {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    fn rgb(&self) -> impl Iterator<Item = u8> + '_ {
+        self.pixels.iter().flat_map(|&i| PALETTE[usize::from(i)])
+    }
+}
+
+/// Encode an image as binary PPM (`P6`).
+pub fn encode_ppm(image: &Image) -> Vec<u8> {
+    let mut out = format!("P6\n{} {}\n255\n", image.width, image.height).into_bytes();
+    out.extend(image.rgb());
+    out
+}
+
+/// Encode an image as an RGB PNG. The image data is stored in uncompressed deflate blocks.
+pub fn encode_png(image: &Image) -> Vec<u8> {
+    let mut out = vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+    let mut header = Vec::with_capacity(13);
+    header.extend((image.width as u32).to_be_bytes());
+    header.extend((image.height as u32).to_be_bytes());
+    // bit depth 8, colour type RGB, default compression, filter and interlace methods.
+    header.extend([8, 2, 0, 0, 0]);
+    write_png_chunk(&mut out, b"IHDR", &header);
+
+    let rgb: Vec<u8> = image.rgb().collect();
+    let mut raw = Vec::with_capacity(image.height * (image.width * 3 + 1));
+    for row in rgb.chunks(image.width * 3) {
+        // filter type "none" for every scanline.
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+    write_png_chunk(&mut out, b"IDAT", &zlib_stored(&raw));
+    write_png_chunk(&mut out, b"IEND", &[]);
+
+    out
+}
+
+fn write_png_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend((data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend(crc.to_be_bytes());
+}
+
+/// Wrap `data` in a zlib stream of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    // CMF / FLG for deflate with a 32K window and no preset dictionary.
+    let mut out = vec![0x78, 0x01];
+
+    let mut blocks = data.chunks(0xffff).peekable();
+    if blocks.peek().is_none() {
+        out.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let len = u16::try_from(block.len()).expect("stored blocks hold at most 0xffff bytes");
+        out.push(u8::from(blocks.peek().is_none()));
+        out.extend(len.to_le_bytes());
+        out.extend((!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+
+    out.extend(adler32(data).to_be_bytes());
+    out
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += u32::from(byte);
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+/// Streaming encoder for looping animated GIFs using [`PALETTE`].
+/// Only the region that changed since the previous frame is encoded.
+pub struct GifEncoder<W: Write> {
+    writer: W,
+    width: usize,
+    height: usize,
+    /// Delay between frames in hundredths of a second.
+    delay: u16,
+    previous: Option<Vec<u8>>,
+}
+
+/// Bits per palette index, the palette has `2^GIF_DEPTH` entries.
+const GIF_DEPTH: u8 = 4;
+
+impl<W: Write> GifEncoder<W> {
+    pub fn new(mut writer: W, width: usize, height: usize, delay: u16) -> io::Result<Self> {
+        writer.write_all(b"GIF89a")?;
+        writer.write_all(&gif_dimension(width)?.to_le_bytes())?;
+        writer.write_all(&gif_dimension(height)?.to_le_bytes())?;
+        // global colour table with 8 bit colour resolution and 2^GIF_DEPTH entries.
+        writer.write_all(&[0xf0 | (GIF_DEPTH - 1), 0, 0])?;
+        for rgb in PALETTE {
+            writer.write_all(&rgb)?;
+        }
+        // loop forever.
+        writer.write_all(&[0x21, 0xff, 0x0b])?;
+        writer.write_all(b"NETSCAPE2.0")?;
+        writer.write_all(&[0x03, 0x01, 0x00, 0x00, 0x00])?;
+
+        Ok(GifEncoder {
+            writer,
+            width,
+            height,
+            delay,
+            previous: None,
+        })
+    }
+
+    pub fn write_frame(&mut self, image: &Image) -> io::Result<()> {
+        let (left, top, width, height) = self.changed_region(image);
+
+        self.writer.write_all(&[0x21, 0xf9, 0x04, 0x04])?;
+        self.writer.write_all(&self.delay.to_le_bytes())?;
+        self.writer.write_all(&[0x00, 0x00])?;
+
+        self.writer.write_all(&[0x2c])?;
+        for value in [left, top, width, height] {
+            self.writer
+                .write_all(&gif_dimension(value)?.to_le_bytes())?;
+        }
+        self.writer.write_all(&[0x00])?;
+
+        let pixels: Vec<u8> = (top..top + height)
+            .flat_map(|y| (left..left + width).map(move |x| (x, y)))
+            .map(|(x, y)| pixel(image, x, y))
+            .collect();
+
+        self.writer.write_all(&[GIF_DEPTH])?;
+        for block in lzw_encode(&pixels, GIF_DEPTH).chunks(255) {
+            self.writer.write_all(&[block.len() as u8])?;
+            self.writer.write_all(block)?;
+        }
+        self.writer.write_all(&[0x00])?;
+
+        self.previous = Some(
+            if (image.width, image.height) == (self.width, self.height) {
+                image.pixels.clone()
+            } else {
+                (0..self.height)
+                    .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+                    .map(|(x, y)| pixel(image, x, y))
+                    .collect()
+            },
+        );
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.write_all(&[0x3b])?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    /// Bounding box (left, top, width, height) of the pixels that differ from the previous frame.
+    fn changed_region(&self, image: &Image) -> (usize, usize, usize, usize) {
+        let Some(previous) = &self.previous else {
+            return (0, 0, self.width, self.height);
+        };
+
+        let mut bounds: Option<(usize, usize, usize, usize)> = None;
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if pixel(image, x, y) != previous[y * self.width + x] {
+                    let (x0, y0, x1, y1) = bounds.unwrap_or((x, y, x, y));
+                    bounds = Some((x0.min(x), y0.min(y), x1.max(x), y1.max(y)));
+                }
+            }
+        }
+
+        // GIF frames can not be empty, re-encode a single pixel if nothing changed.
+        bounds.map_or((0, 0, 1, 1), |(x0, y0, x1, y1)| {
+            (x0, y0, x1 - x0 + 1, y1 - y0 + 1)
+        })
+    }
+}
+
+/// A width, height or offset of a GIF, which are stored in 16 bits.
+fn gif_dimension(value: usize) -> io::Result<u16> {
+    u16::try_from(value).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("GIFs can be at most 65535 pixels wide and high, not {value}"),
+        )
+    })
+}
+
+/// Pixel of `image` at (x, y), background outside of it.
+fn pixel(image: &Image, x: usize, y: usize) -> u8 {
+    if x < image.width && y < image.height {
+        image.pixels[y * image.width + x]
+    } else {
+        0
+    }
+}
+
+/// GIF flavoured LZW: variable code size starting at `min_code_size + 1` bits, packed LSB first.
+fn lzw_encode(indices: &[u8], min_code_size: u8) -> Vec<u8> {
+    const MAX_CODES: usize = 4096;
+
+    let clear = 1u16 << min_code_size;
+    let end = clear + 1;
+    let alphabet = usize::from(clear);
+
+    let mut out = Vec::new();
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    let mut emit = |code: u16, size: u32, out: &mut Vec<u8>| {
+        buffer |= u32::from(code) << bits;
+        bits += size;
+        while bits >= 8 {
+            out.push(buffer as u8);
+            buffer >>= 8;
+            bits -= 8;
+        }
+    };
+
+    // `table[prefix * alphabet + index]` is the code for `prefix` followed by `index`, 0 if unset.
+    let mut table = vec![0u16; MAX_CODES * alphabet];
+    let mut next = end + 1;
+    let mut size = u32::from(min_code_size) + 1;
+
+    emit(clear, size, &mut out);
+
+    let mut iter = indices.iter();
+    if let Some(&first) = iter.next() {
+        let mut prefix = u16::from(first);
+        for &index in iter {
+            let slot = usize::from(prefix) * alphabet + usize::from(index);
+            if table[slot] != 0 {
+                prefix = table[slot];
+                continue;
+            }
+
+            emit(prefix, size, &mut out);
+            if usize::from(next) < MAX_CODES {
+                table[slot] = next;
+                // the decoder widens its codes one code later than it adds them.
+                if next == 1 << size {
+                    size += 1;
+                }
+                next += 1;
+            } else {
+                emit(clear, size, &mut out);
+                table.fill(0);
+                next = end + 1;
+                size = u32::from(min_code_size) + 1;
+            }
+            prefix = u16::from(index);
+        }
+        emit(prefix, size, &mut out);
+        // the decoder still widens its codes after reading the last one.
+        if size < 12 && next == 1 << size {
+            size += 1;
+        }
+    }
+
+    emit(end, size, &mut out);
+    if bits > 0 {
+        out.push(buffer as u8);
+    }
+    out
+}
+
+/// Image formats frames can be exported to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// A sequence of PPM files in a directory.
+    Ppm,
+    /// A sequence of PNG files in a directory.
+    Png,
+    /// A single animated GIF.
+    Gif,
+}
+
+impl Format {
+    fn extension(self) -> &'static str {
+        match self {
+            Format::Ppm => "ppm",
+            Format::Png => "png",
+            Format::Gif => "gif",
+        }
+    }
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ppm" => Ok(Format::Ppm),
+            "png" => Ok(Format::Png),
+            "gif" => Ok(Format::Gif),
+            _ => Err(format!(
+                "unknown export format \"{s}\", expected ppm, png or gif"
+            )),
+        }
+    }
+}
+
+/// Where and how to export frames.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExportOptions {
+    /// Directory for image sequences, file for GIFs.
+    pub path: PathBuf,
+    pub format: Format,
+    /// Size of a grid cell in pixels.
+    pub scale: usize,
+    /// Export only every n-th frame. The final frame is always exported.
+    pub every: usize,
+}
+
+/// Writes the frames of one visualization to disk.
+pub struct Exporter {
+    options: ExportOptions,
+    /// Name of the visualization, used to tell apart the files of several visualizations.
+    name: String,
+    delay: u16,
+    seen: usize,
+    written: usize,
+    gif: Option<GifEncoder<BufWriter<File>>>,
+}
+
+impl Exporter {
+    pub fn new(options: ExportOptions, name: &str, fps: u32) -> Self {
+        // GIF delays are in hundredths of a second, most viewers clamp anything below 2.
+        let delay = 100u32.checked_div(fps).unwrap_or(0).max(2) as u16;
+        Exporter {
+            options,
+            name: name.into(),
+            delay,
+            seen: 0,
+            written: 0,
+            gif: None,
+        }
+    }
+
+    /// The file or directory frames are written to. GIFs get the name of the visualization
+    /// appended, `day06.gif` becomes `day06-walk.gif`, unless the file is already named after it
+    /// like `walk.gif` or `day06-walk.gif`.
+    pub fn target(&self) -> PathBuf {
+        match self.options.format {
+            Format::Gif => {
+                let stem = self
+                    .options
+                    .path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("frames");
+                let file_name = if stem == self.name || stem.ends_with(&format!("-{}", self.name)) {
+                    format!("{stem}.gif")
+                } else {
+                    format!("{stem}-{}.gif", self.name)
+                };
+                self.options.path.with_file_name(file_name)
+            }
+            _ => self.options.path.clone(),
+        }
+    }
+
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
+    /// Export `frame` unless it is skipped by `every`. `force` always exports it.
+    pub fn record(&mut self, frame: &Frame, force: bool) -> io::Result<()> {
+        self.seen += 1;
+        if !force && !(self.seen - 1).is_multiple_of(self.options.every.max(1)) {
+            return Ok(());
+        }
+
+        let image = Image::from_frame(frame, self.options.scale);
+        self.written += 1;
+
+        match self.options.format {
+            Format::Gif => {
+                if self.gif.is_none() {
+                    let target = self.target();
+                    create_parent(&target)?;
+                    let file = BufWriter::new(File::create(target)?);
+                    self.gif = Some(GifEncoder::new(
+                        file,
+                        image.width,
+                        image.height,
+                        self.delay,
+                    )?);
+                }
+                self.gif.as_mut().unwrap().write_frame(&image)
+            }
+            format => {
+                fs::create_dir_all(&self.options.path)?;
+                let path = self.options.path.join(format!(
+                    "{}-{:05}.{}",
+                    self.name,
+                    self.written,
+                    format.extension()
+                ));
+                let data = match format {
+                    Format::Ppm => encode_ppm(&image),
+                    _ => encode_png(&image),
+                };
+                fs::write(path, data)
+            }
+        }
+    }
+
+    pub fn finish(&mut self) -> io::Result<()> {
+        match self.gif.take() {
+            Some(gif) => gif.finish().map(|_| ()),
+            None => Ok(()),
+        }
+    }
+}
+
+fn create_parent(path: &Path) -> io::Result<()> {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => fs::create_dir_all(dir),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal GIF LZW decoder to check the encoder against.
+    fn lzw_decode(data: &[u8], min_code_size: u8) -> Vec<u8> {
+        let clear = 1usize << min_code_size;
+        let end = clear + 1;
+        let mut dict: Vec<Vec<u8>> = Vec::new();
+        let reset = |dict: &mut Vec<Vec<u8>>| {
+            *dict = (0..clear).map(|i| vec![i as u8]).collect();
+            dict.push(vec![]);
+            dict.push(vec![]);
+        };
+        reset(&mut dict);
+
+        let mut size = u32::from(min_code_size) + 1;
+        let (mut buffer, mut bits, mut pos) = (0u32, 0u32, 0usize);
+        let mut previous: Option<Vec<u8>> = None;
+        let mut out = vec![];
+
+        loop {
+            while bits < size {
+                buffer |= u32::from(data[pos]) << bits;
+                pos += 1;
+                bits += 8;
+            }
+            let code = (buffer & ((1 << size) - 1)) as usize;
+            buffer >>= size;
+            bits -= size;
+
+            if code == clear {
+                reset(&mut dict);
+                size = u32::from(min_code_size) + 1;
+                previous = None;
+                continue;
+            }
+            if code == end {
+                return out;
+            }
+
+            let entry = match (dict.get(code), &previous) {
+                (Some(entry), _) if code < dict.len() => entry.clone(),
+                (_, Some(prev)) => {
+                    let mut entry = prev.clone();
+                    entry.push(prev[0]);
+                    entry
+                }
+                _ => panic!("invalid code"),
+            };
+            if let Some(mut prev) = previous.take() {
+                if dict.len() < 4096 {
+                    prev.push(entry[0]);
+                    dict.push(prev);
+                }
+            }
+            if dict.len() == 1 << size && size < 12 {
+                size += 1;
+            }
+            out.extend_from_slice(&entry);
+            previous = Some(entry);
+        }
+    }
+
+    #[test]
+    fn computes_checksums() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn round_trips_lzw() {
+        let short = [1, 1, 1, 2, 2, 3, 1, 1, 1, 1, 1, 0];
+        assert_eq!(lzw_decode(&lzw_encode(&short, 4), 4), short);
+
+        // enough data to fill the code table and force a reset.
+        let long: Vec<u8> = (0..50_000u32)
+            .map(|i| ((i * 7 + i / 13) % 16) as u8)
+            .collect();
+        assert_eq!(lzw_decode(&lzw_encode(&long, 4), 4), long);
+
+        assert_eq!(lzw_decode(&lzw_encode(&[], 4), 4), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn encodes_images() {
+        let mut frame = Frame::from_rows(&["#.", " #"]);
+        frame.paint(0, 0, Color::Red);
+        let image = Image::from_frame(&frame, 2);
+        assert_eq!((image.width, image.height), (4, 4));
+        assert_eq!(image.pixels[..4], [3, 3, 1, 1]);
+        assert_eq!(image.pixels[12..], [0, 0, 1, 1]);
+
+        let ppm = encode_ppm(&image);
+        assert!(ppm.starts_with(b"P6\n4 4\n255\n"));
+        assert_eq!(ppm.len(), 11 + 4 * 4 * 3);
+
+        let png = encode_png(&image);
+        assert!(png.starts_with(&[0x89, b'P', b'N', b'G']));
+        assert!(png.ends_with(&[0xae, 0x42, 0x60, 0x82]));
+    }
+
+    #[test]
+    fn encodes_changed_gif_regions() {
+        let mut frame = Frame::from_rows(&["..", ".."]);
+        let mut gif = GifEncoder::new(Vec::new(), 2, 2, 2).unwrap();
+        gif.write_frame(&Image::from_frame(&frame, 1)).unwrap();
+        frame.paint(1, 1, Color::Green);
+        assert_eq!(
+            gif.changed_region(&Image::from_frame(&frame, 1)),
+            (1, 1, 1, 1)
+        );
+        gif.write_frame(&Image::from_frame(&frame, 1)).unwrap();
+
+        let data = gif.finish().unwrap();
+        assert!(data.starts_with(b"GIF89a"));
+        assert_eq!(data.last(), Some(&0x3b));
+    }
+
+    #[test]
+    fn rejects_oversized_gifs() {
+        let error = GifEncoder::new(Vec::new(), 70_000, 2, 2).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn names_gifs_after_visualizations() {
+        let target = |path: &str| {
+            let options = ExportOptions {
+                path: PathBuf::from(path),
+                format: Format::Gif,
+                scale: 4,
+                every: 1,
+            };
+            Exporter::new(options, "walk", 30).target()
+        };
+        assert_eq!(target("out/day06.gif"), PathBuf::from("out/day06-walk.gif"));
+        assert_eq!(target("walk.gif"), PathBuf::from("walk.gif"));
+        assert_eq!(target("day06-walk.gif"), PathBuf::from("day06-walk.gif"));
+    }
+}
@@ -0,0 +1,480 @@
+//! Terminal rendering for grid puzzles.
+//!
+//! Solutions build a [`Frame`] per step and hand it to a [`Visualizer`], which draws the part
+//! of the grid that fits the terminal and controls playback. All of this only happens when the
+//! crate is built with the `visualize` feature and the bin is run with `--visualize`, e.g. via
+//! `cargo solve <day> --visualize`. Otherwise [`requested`] is `false` and solutions skip
+//! building frames entirely.
+//!
+//! With `--export <path>`, frames are written to image files instead, see [`export`].
+use std::{
+    env,
+    fmt::Write as _,
+    fs::File,
+    io::{self, BufRead, Write},
+    path::PathBuf,
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use export::{ExportOptions, Exporter, Format};
+
+pub mod export;
+
+const DEFAULT_FPS: u32 = 30;
+
+/// Size of a grid cell in exported images, in pixels.
+const DEFAULT_SCALE: usize = 4;
+
+/// Lines reserved below the grid for the caption and status line.
+const RESERVED_LINES: usize = 2;
+
+/// Whether the current run should be visualized.
+/// Timed runs are never visualized, drawing frames would distort the measurements.
+pub fn requested() -> bool {
+    cfg!(feature = "visualize")
+        && env::args().any(|arg| arg == "--visualize")
+        && !env::args().any(|arg| arg == "--time")
+}
+
+/// Foreground colours supported by [`Frame`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Color {
+    #[default]
+    Default,
+    Gray,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl Color {
+    fn ansi(self) -> &'static str {
+        match self {
+            Color::Default => "\x1b[0m",
+            Color::Gray => "\x1b[90m",
+            Color::Red => "\x1b[1;31m",
+            Color::Green => "\x1b[32m",
+            Color::Yellow => "\x1b[1;33m",
+            Color::Blue => "\x1b[34m",
+            Color::Magenta => "\x1b[35m",
+            Color::Cyan => "\x1b[36m",
+            Color::White => "\x1b[1;37m",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    pub color: Color,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Cell {
+            ch: ' ',
+            color: Color::Default,
+        }
+    }
+}
+
+/// A single image of a grid, plus a caption shown below it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+    pub caption: String,
+}
+
+impl Frame {
+    pub fn new(width: usize, height: usize) -> Self {
+        Frame {
+            width,
+            height,
+            cells: vec![Cell::default(); width * height],
+            caption: String::new(),
+        }
+    }
+
+    /// Create a frame from byte rows, e.g. the parsed puzzle grid, in the default colour.
+    pub fn from_rows<R: AsRef<[u8]>>(rows: &[R]) -> Self {
+        let height = rows.len();
+        let width = rows.iter().map(|r| r.as_ref().len()).max().unwrap_or(0);
+        let mut frame = Frame::new(width, height);
+        for (row, line) in rows.iter().enumerate() {
+            for (col, &b) in line.as_ref().iter().enumerate() {
+                frame.set(row, col, b as char, Color::Default);
+            }
+        }
+        frame
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<Cell> {
+        (row < self.height && col < self.width).then(|| self.cells[row * self.width + col])
+    }
+
+    /// Set a cell. Positions outside of the frame are ignored.
+    pub fn set(&mut self, row: usize, col: usize, ch: char, color: Color) {
+        if row < self.height && col < self.width {
+            self.cells[row * self.width + col] = Cell { ch, color };
+        }
+    }
+
+    /// Recolour a cell, keeping its character.
+    pub fn paint(&mut self, row: usize, col: usize, color: Color) {
+        if let Some(cell) = self.get(row, col) {
+            self.set(row, col, cell.ch, color);
+        }
+    }
+
+    /// Render the part of the frame visible in `viewport`, without the caption.
+    pub fn render(&self, viewport: &Viewport) -> String {
+        let mut out = String::new();
+        let rows = viewport.top..(viewport.top + viewport.rows).min(self.height);
+        let cols = viewport.left..(viewport.left + viewport.cols).min(self.width);
+
+        for row in rows {
+            let mut color = Color::Default;
+            for col in cols.clone() {
+                let cell = self.cells[row * self.width + col];
+                // only emit escape codes when the colour changes.
+                if cell.color != color {
+                    out.push_str(cell.color.ansi());
+                    color = cell.color;
+                }
+                out.push(cell.ch);
+            }
+            if color != Color::Default {
+                out.push_str(Color::Default.ansi());
+            }
+            out.push('\n');
+        }
+
+        out
+    }
+}
+
+/// The window of a frame that is drawn to the terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Viewport {
+    pub top: usize,
+    pub left: usize,
+    pub rows: usize,
+    pub cols: usize,
+}
+
+impl Viewport {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Viewport {
+            top: 0,
+            left: 0,
+            rows,
+            cols,
+        }
+    }
+
+    /// Scroll so that `focus` (row, col) stays visible.
+    /// The viewport only moves once the focus gets close to its edges, then re-centres on it.
+    pub fn follow(&mut self, focus: (usize, usize), height: usize, width: usize) {
+        fn axis(start: &mut usize, size: usize, focus: usize, total: usize) {
+            if total <= size {
+                *start = 0;
+                return;
+            }
+            let margin = size / 4;
+            if focus < *start + margin || focus + margin >= *start + size {
+                *start = focus.saturating_sub(size / 2);
+            }
+            *start = (*start).min(total - size);
+        }
+
+        axis(&mut self.top, self.rows, focus.0, height);
+        axis(&mut self.left, self.cols, focus.1, width);
+    }
+}
+
+/// Playback settings, read from the arguments of the bin.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Options {
+    /// Frames per second. `0` draws frames as fast as possible.
+    pub fps: u32,
+    /// Wait for `Enter` after every frame.
+    pub step: bool,
+    /// Write frames to image files instead of drawing them to the terminal.
+    pub export: Option<ExportOptions>,
+}
+
+impl Options {
+    /// Parse `--fps <n>`, `--step` and the export options from the command line:
+    /// `--export <path>`, `--format <ppm|png|gif>`, `--scale <n>` and `--every <n>`.
+    /// Paths ending in `.gif` are exported as GIF, other paths as a directory of PNG files.
+    pub fn from_args() -> Self {
+        let args: Vec<String> = env::args().collect();
+        Self::parse(&args)
+    }
+
+    fn parse(args: &[String]) -> Self {
+        let value = |name: &str| {
+            args.iter()
+                .position(|arg| arg == name)
+                .and_then(|i| args.get(i + 1))
+        };
+        let number = |name: &str| value(name).and_then(|v| v.parse().ok());
+
+        let export = value("--export").map(|path| {
+            let path = PathBuf::from(path);
+            let format = match value("--format").map(|f| f.parse::<Format>()) {
+                Some(Ok(format)) => format,
+                Some(Err(e)) => {
+                    eprintln!("{e}, exporting as png.");
+                    Format::Png
+                }
+                None if path.extension().is_some_and(|ext| ext == "gif") => Format::Gif,
+                None => Format::Png,
+            };
+            ExportOptions {
+                path,
+                format,
+                scale: number("--scale").unwrap_or(DEFAULT_SCALE),
+                every: number("--every").unwrap_or(1),
+            }
+        });
+
+        Options {
+            fps: number("--fps").map_or(DEFAULT_FPS, |fps| fps as u32),
+            step: args.iter().any(|arg| arg == "--step"),
+            export,
+        }
+    }
+}
+
+/// Draws frames to the terminal at the configured speed, or exports them.
+pub struct Visualizer {
+    options: Options,
+    viewport: Viewport,
+    frames: usize,
+    last_frame: Option<Instant>,
+    /// Set once the user quits playback in step mode, remaining frames are skipped.
+    stopped: bool,
+    exporter: Option<Exporter>,
+}
+
+impl Visualizer {
+    /// Create a visualizer configured from the command line.
+    /// `name` tells apart exported files if a solution has several visualizations.
+    pub fn new(name: &str) -> Self {
+        Self::with_options(name, Options::from_args())
+    }
+
+    pub fn with_options(name: &str, options: Options) -> Self {
+        let (rows, cols) = terminal_size();
+        let exporter = options
+            .export
+            .clone()
+            .map(|export| Exporter::new(export, name, options.fps));
+        Visualizer {
+            options,
+            viewport: Viewport::new(rows.saturating_sub(RESERVED_LINES).max(1), cols),
+            frames: 0,
+            last_frame: None,
+            stopped: false,
+            exporter,
+        }
+    }
+
+    /// Draw `frame`, scrolling the viewport to keep `focus` (row, col) visible.
+    pub fn show(&mut self, frame: &Frame, focus: Option<(usize, usize)>) {
+        if self.stopped {
+            return;
+        }
+
+        if let Some(exporter) = &mut self.exporter {
+            if let Err(e) = exporter.record(frame, false) {
+                eprintln!("Failed to export frame: {e}");
+                self.stopped = true;
+            }
+            return;
+        }
+
+        if let Some(focus) = focus {
+            self.viewport.follow(focus, frame.height, frame.width);
+        }
+
+        self.wait_for_next_frame();
+        self.frames += 1;
+
+        let mut out = String::from("\x1b[H\x1b[2J");
+        out.push_str(&frame.render(&self.viewport));
+        out.push_str(&frame.caption);
+        let _ = write!(
+            out,
+            "\n\x1b[90mframe {} · rows {}-{} of {} · cols {}-{} of {}{}\x1b[0m",
+            self.frames,
+            self.viewport.top + 1,
+            (self.viewport.top + self.viewport.rows).min(frame.height),
+            frame.height,
+            self.viewport.left + 1,
+            (self.viewport.left + self.viewport.cols).min(frame.width),
+            frame.width,
+            if self.options.step {
+                " · Enter: next, q: skip to end"
+            } else {
+                ""
+            }
+        );
+
+        let mut stdout = io::stdout().lock();
+        let _ = stdout.write_all(out.as_bytes());
+        let _ = stdout.flush();
+
+        if self.options.step {
+            let mut line = String::new();
+            let _ = io::stdin().lock().read_line(&mut line);
+            if line.trim() == "q" {
+                self.stopped = true;
+            }
+        }
+    }
+
+    /// Draw the last frame regardless of playback state, and move the cursor below it.
+    pub fn finish(&mut self, frame: &Frame, focus: Option<(usize, usize)>) {
+        if let Some(exporter) = &mut self.exporter {
+            let result = exporter
+                .record(frame, true)
+                .and_then(|()| exporter.finish());
+            match result {
+                Ok(()) => println!(
+                    "Exported {} frames to \"{}\"",
+                    exporter.written(),
+                    exporter.target().display()
+                ),
+                Err(e) => eprintln!("Failed to export frames: {e}"),
+            }
+            return;
+        }
+
+        self.stopped = false;
+        self.options.step = false;
+        self.last_frame = None;
+        self.show(frame, focus);
+        println!();
+    }
+
+    fn wait_for_next_frame(&mut self) {
+        if self.options.fps > 0 && !self.options.step {
+            let interval = Duration::from_secs(1) / self.options.fps;
+            if let Some(elapsed) = self.last_frame.map(|t| t.elapsed()) {
+                if elapsed < interval {
+                    thread::sleep(interval - elapsed);
+                }
+            }
+        }
+        self.last_frame = Some(Instant::now());
+    }
+}
+
+/// Size of the terminal as (rows, columns).
+/// Prefers `LINES` / `COLUMNS`, then asks `stty`, and falls back to 24x80.
+fn terminal_size() -> (usize, usize) {
+    let from_env = |name| env::var(name).ok()?.parse::<usize>().ok();
+    if let (Some(rows), Some(cols)) = (from_env("LINES"), from_env("COLUMNS")) {
+        return (rows, cols);
+    }
+
+    File::open("/dev/tty")
+        .ok()
+        .and_then(|tty| {
+            let output = Command::new("stty")
+                .arg("size")
+                .stdin(tty)
+                .stderr(Stdio::null())
+                .output()
+                .ok()?;
+            let size = String::from_utf8(output.stdout).ok()?;
+            let (rows, cols) = size.trim().split_once(' ')?;
+            Some((rows.parse().ok()?, cols.parse().ok()?))
+        })
+        .unwrap_or((24, 80))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_viewport_with_colours() {
+        let mut frame = Frame::from_rows(&["abc", "def", "ghi"]);
+        frame.paint(1, 1, Color::Red);
+
+        let viewport = Viewport {
+            top: 1,
+            left: 1,
+            rows: 5,
+            cols: 5,
+        };
+        assert_eq!(
+            frame.render(&viewport),
+            format!("{}e{}f\nhi\n", Color::Red.ansi(), Color::Default.ansi())
+        );
+    }
+
+    #[test]
+    fn follows_focus() {
+        let mut viewport = Viewport::new(10, 10);
+
+        viewport.follow((3, 3), 100, 100);
+        assert_eq!((viewport.top, viewport.left), (0, 0));
+
+        viewport.follow((50, 8), 100, 100);
+        assert_eq!((viewport.top, viewport.left), (45, 3));
+
+        viewport.follow((99, 99), 100, 100);
+        assert_eq!((viewport.top, viewport.left), (90, 90));
+
+        viewport.follow((50, 50), 5, 5);
+        assert_eq!((viewport.top, viewport.left), (0, 0));
+    }
+
+    #[test]
+    fn parses_options() {
+        let args = ["06", "--visualize", "--fps", "120", "--step"].map(String::from);
+        assert_eq!(
+            Options::parse(&args),
+            Options {
+                fps: 120,
+                step: true,
+                export: None,
+            }
+        );
+        assert_eq!(Options::parse(&[]).fps, DEFAULT_FPS);
+
+        let args = ["06", "--export", "out/walk.gif", "--every", "10"].map(String::from);
+        assert_eq!(
+            Options::parse(&args).export,
+            Some(ExportOptions {
+                path: "out/walk.gif".into(),
+                format: Format::Gif,
+                scale: DEFAULT_SCALE,
+                every: 10,
+            })
+        );
+
+        let args = ["06", "--export", "frames", "--format", "ppm"].map(String::from);
+        assert_eq!(Options::parse(&args).export.unwrap().format, Format::Ppm);
+    }
+}